@@ -16,12 +16,15 @@ use tracing::{error, info, warn};
 use elfo_core::{
     config::AnyConfig,
     errors::RequestError,
+    message,
     messages::{
         EntrypointError, Ping, StartEntrypoint, StartEntrypointRejected, UpdateConfig,
         ValidateConfig,
     },
     msg, scope,
     signal::{Signal, SignalKind},
+    time::Debounce,
+    watch::Watch,
     ActorGroup, ActorStatus, Addr, Blueprint, Context, RestartParams, RestartPolicy, Topology,
 };
 
@@ -32,6 +35,8 @@ mod protocol;
 
 // How often warn if a group is updating a config too long.
 const WARN_INTERVAL: Duration = Duration::from_secs(5);
+// Writers can change the config file in several steps, so wait for the end.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
 
 pub fn fixture(topology: &Topology, config: impl for<'de> Deserializer<'de>) -> Blueprint {
     let config = Value::deserialize(config).map_err(|err| err.to_string());
@@ -40,7 +45,19 @@ pub fn fixture(topology: &Topology, config: impl for<'de> Deserializer<'de>) ->
 }
 
 pub fn from_path(topology: &Topology, path_to_config: impl AsRef<Path>) -> Blueprint {
-    let source = ConfigSource::File(path_to_config.as_ref().to_path_buf());
+    let source = ConfigSource::File {
+        path: path_to_config.as_ref().to_path_buf(),
+        watch: false,
+    };
+    blueprint(topology, source)
+}
+
+/// Like [`from_path`], but also reloads configs once the file changes.
+pub fn from_path_watched(topology: &Topology, path_to_config: impl AsRef<Path>) -> Blueprint {
+    let source = ConfigSource::File {
+        path: path_to_config.as_ref().to_path_buf(),
+        watch: true,
+    };
     blueprint(topology, source)
 }
 
@@ -60,15 +77,20 @@ struct Configurer {
     topology: Topology,
     source: ConfigSource,
     /// Stores hashes of configs per group.
-    versions: FxHashMap<String, u64>,
+    /// Groups can be remounted at runtime with the same name, so addresses
+    /// are used as keys instead of names.
+    versions: FxHashMap<Addr, u64>,
 }
 
 #[derive(Clone)]
 enum ConfigSource {
-    File(PathBuf),
+    File { path: PathBuf, watch: bool },
     Fixture(Result<Value, String>),
 }
 
+#[message]
+struct ConfigFileChanged;
+
 #[derive(Clone)]
 struct ConfigWithMeta {
     group_name: String,
@@ -150,6 +172,14 @@ impl Configurer {
         let signal = Signal::new(SignalKind::UnixUser2, ReloadConfigs::forcing());
         self.ctx.attach(signal);
 
+        let debounce = match &self.source {
+            ConfigSource::File { path, watch: true } => {
+                self.ctx.attach(Watch::new(path, ConfigFileChanged));
+                Some(self.ctx.attach(Debounce::last(WATCH_DEBOUNCE)))
+            }
+            _ => None,
+        };
+
         while let Some(envelope) = match first_envelope.take() {
             e @ Some(..) => e,
             None => self.ctx.recv().await,
@@ -163,13 +193,18 @@ impl Configurer {
 
                     self.ctx.respond(token, response);
                 }
+                ConfigFileChanged => {
+                    if let Some(debounce) = &debounce {
+                        debounce.push(ReloadConfigs::default());
+                    }
+                }
             })
         }
     }
 
     async fn load_configs(&self) -> Result<Value, Vec<ReloadConfigsError>> {
         let config = match &self.source {
-            ConfigSource::File(path) => {
+            ConfigSource::File { path, .. } => {
                 info!(message = "loading a config", path = %path.to_string_lossy());
                 load_raw_config(path).await
             }
@@ -216,13 +251,13 @@ impl Configurer {
 
         let mut configs = match_configs(&self.topology, &configs);
 
+        // Forget unmounted groups.
+        self.versions
+            .retain(|addr, _| configs.iter().any(|c| c.addr == *addr));
+
         // Filter out up-to-date configs if needed.
         if !force {
-            configs.retain(|c| {
-                self.versions
-                    .get(&c.group_name)
-                    .map_or(true, |v| c.hash != *v)
-            });
+            configs.retain(|c| self.versions.get(&c.addr).map_or(true, |v| c.hash != *v));
         }
 
         if configs.is_empty() {
@@ -255,7 +290,7 @@ impl Configurer {
         let updated_groups: Vec<String> = configs
             .into_iter()
             .inspect(|config| {
-                self.versions.insert(config.group_name.clone(), config.hash);
+                self.versions.insert(config.addr, config.hash);
             })
             .map(|config| config.group_name)
             .collect();
@@ -370,6 +405,7 @@ async fn ping(ctx: &Context, config_list: &[ConfigWithMeta]) -> bool {
         .filter_map(|result| match result {
             Ok(()) | Err(RequestError::Ignored) => None,
             Err(RequestError::Failed) => Some(String::from("some group is closed")),
+            Err(RequestError::TimedOut) => Some(String::from("some group hasn't responded")),
         })
         // TODO: include actor keys in the error message.
         .inspect(|reason| error!(%reason, "ping failed"));
@@ -1,8 +1,15 @@
-use std::{fmt, mem, sync::Arc};
+use std::{
+    fmt, mem,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use futures_intrusive::sync::ManualResetEvent;
 use metrics::{decrement_gauge, increment_counter, increment_gauge};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 
@@ -10,8 +17,8 @@ use crate::{
     envelope::Envelope,
     errors::{SendError, TrySendError},
     group::TerminationPolicy,
-    mailbox::{Mailbox, RecvResult},
-    messages::{ActorStatusReport, Terminate},
+    mailbox::{Mailbox, MailboxParams, RecvResult},
+    messages::{ActorStatusReport, Down, Terminate},
     msg,
     request_table::RequestTable,
     restarting::RestartPolicy,
@@ -139,6 +146,9 @@ pub enum ActorStartCause {
     OnMessage,
     /// The actor started due to the restart policy.
     Restarted,
+    /// The actor started in response to a message after passivation,
+    /// see `ActorGroup::idle_timeout()`.
+    Reactivated,
 }
 
 impl ActorStartInfo {
@@ -159,6 +169,12 @@ impl ActorStartInfo {
             cause: ActorStartCause::Restarted,
         }
     }
+
+    pub(crate) fn on_reactivation() -> Self {
+        Self {
+            cause: ActorStartCause::Reactivated,
+        }
+    }
 }
 
 impl ActorStartCause {
@@ -173,47 +189,84 @@ impl ActorStartCause {
     pub fn is_on_message(&self) -> bool {
         matches!(self, ActorStartCause::OnMessage)
     }
+
+    pub fn is_reactivated(&self) -> bool {
+        matches!(self, ActorStartCause::Reactivated)
+    }
 }
 
 // === Actor ===
 
 pub(crate) struct Actor {
     meta: Arc<ActorMeta>,
+    addr: Addr,
     termination_policy: TerminationPolicy,
     mailbox: Mailbox,
     request_table: RequestTable,
     control: RwLock<ControlBlock>,
     finished: ManualResetEvent, // TODO: remove in favor of `status_subscription`?
     status_subscription: Arc<SubscriptionManager>,
+    /// Actors waiting for `Down`, see `Context::monitor()`.
+    monitors: SubscriptionManager,
+    /// Actors failing together with this one, see `Context::link()`.
+    links: Mutex<Vec<Addr>>,
+    /// The number of starts with the same key, including this one.
+    spawn_count: u32,
+    passivated: AtomicBool,
+    /// Called before closing the mailbox on passivation, see `passivate()`.
+    on_passivate: Option<Box<dyn Fn() + Send + Sync>>,
+    restart_requested: AtomicBool,
 }
 
 struct ControlBlock {
     status: ActorStatus,
     /// If `None`, a group's policy will be used.
     restart_policy: Option<RestartPolicy>,
+    /// Set if a linked actor has failed, see `Context::link()`.
+    linked_failure: Option<String>,
 }
 
 impl Actor {
     pub(crate) fn new(
         meta: Arc<ActorMeta>,
         addr: Addr,
+        mailbox_params: MailboxParams,
         termination_policy: TerminationPolicy,
         status_subscription: Arc<SubscriptionManager>,
+        spawn_count: u32,
     ) -> Self {
+        let monitors = SubscriptionManager::new(status_subscription.context().clone());
+
         Actor {
             meta,
+            addr,
             termination_policy,
-            mailbox: Mailbox::new(),
+            mailbox: Mailbox::new(mailbox_params),
             request_table: RequestTable::new(addr),
             control: RwLock::new(ControlBlock {
                 status: ActorStatus::INITIALIZING,
                 restart_policy: None,
+                linked_failure: None,
             }),
             finished: ManualResetEvent::new(false),
             status_subscription,
+            monitors,
+            links: Mutex::new(Vec::new()),
+            spawn_count,
+            passivated: AtomicBool::new(false),
+            on_passivate: None,
+            restart_requested: AtomicBool::new(false),
         }
     }
 
+    /// Sets the callback, which is called on passivation before closing
+    /// the mailbox. Used by the supervisor to forget the actor, so that
+    /// new messages start another one instead of being lost.
+    pub(crate) fn with_on_passivate(mut self, f: impl Fn() + Send + Sync + 'static) -> Self {
+        self.on_passivate = Some(Box::new(f));
+        self
+    }
+
     pub(crate) fn on_start(&self) {
         increment_gauge!("elfo_active_actors", 1.,
             "status" => ActorStatusKind::Initializing.as_str());
@@ -263,6 +316,26 @@ impl Actor {
         self.mailbox.try_recv()
     }
 
+    pub(crate) fn configure_mailbox(&self, params: MailboxParams) {
+        self.mailbox.configure(params);
+    }
+
+    pub(crate) fn stash_capacity(&self) -> usize {
+        self.mailbox.stash_capacity()
+    }
+
+    pub(crate) fn mailbox_len(&self) -> usize {
+        self.mailbox.len()
+    }
+
+    pub(crate) fn spawn_count(&self) -> u32 {
+        self.spawn_count
+    }
+
+    pub(crate) fn status_kind(&self) -> ActorStatusKind {
+        self.control.read().status.kind
+    }
+
     pub(crate) fn request_table(&self) -> &RequestTable {
         &self.request_table
     }
@@ -285,8 +358,35 @@ impl Actor {
         }
 
         self.send_status_to_subscribers(&control);
+
+        // Under the lock to avoid races with `add_monitor()` and `add_link()`.
+        if status.is_finished() {
+            self.monitors.send(Down {
+                addr: self.addr.into(),
+                status: status.clone(),
+            });
+        }
+        let links = if status.is_finished() {
+            mem::take(&mut *self.links.lock())
+        } else {
+            Vec::new()
+        };
+
         drop(control);
 
+        // Outside the lock, because linked actors can fail at the same time.
+        let book = self.status_subscription.context().book();
+        for addr in links {
+            let object = ward!(book.get(addr), continue);
+            let actor = ward!(object.as_actor(), continue);
+
+            if status.is_failed() {
+                actor.on_link_failed(self.addr);
+            } else {
+                actor.remove_link(self.addr);
+            }
+        }
+
         if status.is_finished() {
             self.close();
             // Drop all messages to release requests immediately.
@@ -315,6 +415,89 @@ impl Actor {
         self.mailbox.close(scope::trace_id())
     }
 
+    pub(crate) fn idle_timeout(&self) -> Option<Duration> {
+        self.mailbox.idle_timeout()
+    }
+
+    /// Closes the mailbox of the idle actor, see `ActorGroup::idle_timeout()`.
+    pub(crate) fn passivate(&self) {
+        if self.passivated.swap(true, Ordering::Relaxed) {
+            return;
+        }
+
+        if let Some(on_passivate) = &self.on_passivate {
+            on_passivate();
+        }
+
+        self.close();
+    }
+
+    pub(crate) fn is_passivated(&self) -> bool {
+        self.passivated.load(Ordering::Relaxed)
+    }
+
+    /// Closes the mailbox and restarts the actor regardless of its restart
+    /// policy, see `SupervisionStrategy`.
+    pub(crate) fn request_restart(&self) {
+        self.restart_requested.store(true, Ordering::Relaxed);
+        self.close();
+    }
+
+    pub(crate) fn is_restart_requested(&self) -> bool {
+        self.restart_requested.load(Ordering::Relaxed)
+    }
+
+    /// Returns the status if the actor has already finished.
+    pub(crate) fn add_monitor(&self, addr: Addr) -> Result<(), ActorStatus> {
+        let control = self.control.read();
+
+        if control.status.is_finished() {
+            return Err(control.status.clone());
+        }
+
+        self.monitors.add(addr);
+        Ok(())
+    }
+
+    pub(crate) fn remove_monitor(&self, addr: Addr) {
+        self.monitors.remove(addr);
+    }
+
+    /// Returns the status if the actor has already finished.
+    pub(crate) fn add_link(&self, addr: Addr) -> Result<(), ActorStatus> {
+        let control = self.control.read();
+
+        if control.status.is_finished() {
+            return Err(control.status.clone());
+        }
+
+        let mut links = self.links.lock();
+        if !links.contains(&addr) {
+            links.push(addr);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove_link(&self, addr: Addr) {
+        self.links.lock().retain(|stored| *stored != addr);
+    }
+
+    /// Closes the mailbox, the actor will be considered failed.
+    pub(crate) fn fail_linked(&self, details: String) {
+        self.control.write().linked_failure.get_or_insert(details);
+        self.close();
+    }
+
+    pub(crate) fn take_linked_failure(&self) -> Option<String> {
+        self.control.write().linked_failure.take()
+    }
+
+    fn on_link_failed(&self, addr: Addr) {
+        // The failed actor is already dead, don't notify it back.
+        self.remove_link(addr);
+        self.fail_linked(format!("linked actor {addr} failed"));
+    }
+
     pub(crate) fn is_initializing(&self) -> bool {
         matches!(
             self.control.read().status.kind,
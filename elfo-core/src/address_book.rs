@@ -1,9 +1,11 @@
 use std::sync::Arc;
 
+use once_cell::sync::OnceCell;
 use sharded_slab::{self as slab, Slab};
 
 use crate::{
     addr::{Addr, GroupNo, NodeLaunchId, NodeNo, SlabConfig},
+    dead_letters::DeadLetters,
     object::{Object, ObjectArc, ObjectRef},
 };
 
@@ -12,6 +14,12 @@ use crate::{
 pub struct AddressBook {
     launch_id: NodeLaunchId,
     local: Arc<Slab<Object, SlabConfig>>,
+    /// The address of the `system.init` actor, which terminates the system.
+    system_init: Arc<OnceCell<Addr>>,
+    /// The address of the group receiving failures, see `Local::escalation()`.
+    escalation: Arc<OnceCell<Addr>>,
+    /// The group receiving undeliverable messages, see `Local::deadletter()`.
+    dead_letters: Arc<OnceCell<DeadLetters>>,
     #[cfg(feature = "network")]
     remote: Arc<RemoteToHandleMap>, // TODO: use `arc_swap::cache::Cache` in TLS?
 }
@@ -26,11 +34,46 @@ impl AddressBook {
         return Self {
             launch_id,
             local,
+            system_init: Default::default(),
+            escalation: Default::default(),
+            dead_letters: Default::default(),
             remote: Default::default(),
         };
 
         #[cfg(not(feature = "network"))]
-        Self { launch_id, local }
+        Self {
+            launch_id,
+            local,
+            system_init: Default::default(),
+            escalation: Default::default(),
+            dead_letters: Default::default(),
+        }
+    }
+
+    pub(crate) fn set_system_init(&self, addr: Addr) {
+        let _ = self.system_init.set(addr);
+    }
+
+    pub(crate) fn system_init(&self) -> Option<Addr> {
+        self.system_init.get().copied()
+    }
+
+    /// Returns `false` if the escalation group is already set.
+    pub(crate) fn set_escalation(&self, addr: Addr) -> bool {
+        self.escalation.set(addr).is_ok()
+    }
+
+    pub(crate) fn escalation(&self) -> Option<Addr> {
+        self.escalation.get().copied()
+    }
+
+    /// Returns `false` if the deadletter group is already set.
+    pub(crate) fn set_dead_letters(&self, addr: Addr) -> bool {
+        self.dead_letters.set(DeadLetters::new(addr)).is_ok()
+    }
+
+    pub(crate) fn dead_letters(&self) -> Option<&DeadLetters> {
+        self.dead_letters.get()
     }
 
     #[cfg(feature = "network")]
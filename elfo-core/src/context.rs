@@ -1,6 +1,7 @@
-use std::{future::poll_fn, marker::PhantomData, pin::Pin, sync::Arc, task::Poll};
+use std::{future::poll_fn, pin::Pin, sync::Arc, task::Poll, time::Duration};
 
 use futures::{pin_mut, Stream};
+use metrics::increment_counter;
 use once_cell::sync::Lazy;
 use tracing::{info, trace};
 
@@ -14,21 +15,22 @@ use crate::{
     demux::Demux,
     dumping::{Direction, Dump, Dumper, INTERNAL_CLASS},
     envelope::{AnyMessageBorrowed, AnyMessageOwned, Envelope, EnvelopeOwned, MessageKind},
-    errors::{RequestError, SendError, TryRecvError, TrySendError},
+    errors::{RecvTimeoutError, RequestError, SendError, StashError, TryRecvError, TrySendError},
     mailbox::RecvResult,
     message::{Message, Request},
     messages, msg,
     object::ObjectArc,
-    request_table::ResponseToken,
-    restarting::RestartPolicy,
+    request_table::{RequestId, RequestMode, ResponseToken, Responses},
+    restarting::{RestartBackoff, RestartPolicy, RetryPolicy},
     routers::Singleton,
     scope,
     source::{SourceHandle, Sources, UnattachedSource},
 };
 
-use self::{budget::Budget, stats::Stats};
+use self::{budget::Budget, stash::Stash, stats::Stats};
 
 mod budget;
+mod stash;
 mod stats;
 
 static DUMPER: Lazy<Dumper> = Lazy::new(|| Dumper::new(INTERNAL_CLASS));
@@ -47,12 +49,16 @@ pub struct Context<C = (), K = Singleton> {
     stage: Stage,
     stats: Stats,
     budget: Budget,
+    stash: Stash,
 }
 
 #[derive(Clone, Copy, PartialEq)]
 enum Stage {
     PreRecv,
     Working,
+    /// The input is closed, but envelopes skipped by `recv_matching()`
+    /// haven't been received yet.
+    Draining,
     Closed,
 }
 
@@ -151,7 +157,13 @@ impl<C, K> Context<C, K> {
         let kind = MessageKind::Regular {
             sender: self.actor_addr,
         };
-        self.do_send(message, kind).await
+        let result = self.do_send(message, kind).await;
+
+        if let Err(SendError(message)) = &result {
+            self.on_undelivered(message, None);
+        }
+
+        result
     }
 
     /// Tries to send a message using the routing system.
@@ -175,6 +187,16 @@ impl<C, K> Context<C, K> {
     /// }
     /// ```
     pub fn try_send<M: Message>(&self, message: M) -> Result<(), TrySendError<M>> {
+        let result = self.do_try_send(message);
+
+        if let Err(TrySendError::Closed(message)) = &result {
+            self.on_undelivered(message, None);
+        }
+
+        result
+    }
+
+    fn do_try_send<M: Message>(&self, message: M) -> Result<(), TrySendError<M>> {
         let kind = MessageKind::Regular {
             sender: self.actor_addr,
         };
@@ -347,7 +369,13 @@ impl<C, K> Context<C, K> {
         let kind = MessageKind::Regular {
             sender: self.actor_addr,
         };
-        self.do_send_to(recipient, message, kind).await
+        let result = self.do_send_to(recipient, message, kind).await;
+
+        if let Err(SendError(message)) = &result {
+            self.on_undelivered(message, Some(recipient));
+        }
+
+        result
     }
 
     async fn do_send_to<M: Message>(
@@ -392,6 +420,20 @@ impl<C, K> Context<C, K> {
         &self,
         recipient: Addr,
         message: M,
+    ) -> Result<(), TrySendError<M>> {
+        let result = self.do_try_send_to(recipient, message);
+
+        if let Err(TrySendError::Closed(message)) = &result {
+            self.on_undelivered(message, Some(recipient));
+        }
+
+        result
+    }
+
+    fn do_try_send_to<M: Message>(
+        &self,
+        recipient: Addr,
+        message: M,
     ) -> Result<(), TrySendError<M>> {
         self.stats.on_sent_message(&message);
 
@@ -413,6 +455,29 @@ impl<C, K> Context<C, K> {
             .map_err(|err| err.map(e2m))
     }
 
+    /// Sends the message to the deadletter group, see `Local::deadletter()`.
+    #[cold]
+    fn on_undelivered<M: Message>(&self, message: &M, intended_route: Option<Addr>) {
+        // Avoid loops if the deadletter group is unavailable.
+        if (message as &dyn std::any::Any).is::<messages::DeadLetter>() {
+            return;
+        }
+
+        increment_counter!("elfo_dead_letters_total", "message" => message.name());
+
+        let dead_letters = ward!(self.book.dead_letters());
+        if !dead_letters.acquire(message) {
+            return;
+        }
+
+        let letter = messages::DeadLetter {
+            original_sender: self.actor_addr.into(),
+            intended_route: intended_route.into(),
+            message: message.clone().upcast(),
+        };
+        let _ = self.try_send_to(dead_letters.addr(), letter);
+    }
+
     /// Responds to the requester with the provided response.
     ///
     /// The token can be used only once.
@@ -486,39 +551,18 @@ impl<C, K> Context<C, K> {
     where
         C: 'static,
     {
-        'outer: loop {
-            // TODO: reset if the mailbox is empty.
-            self.budget.acquire().await;
-
-            self.pre_recv();
-
-            let envelope = 'received: {
-                let mailbox_fut = self.actor.as_ref()?.as_actor()?.recv();
-                pin_mut!(mailbox_fut);
-
-                tokio::select! {
-                    result = mailbox_fut => match result {
-                        RecvResult::Data(envelope) => {
-                            break 'received envelope;
-                        },
-                        RecvResult::Closed(trace_id) => {
-                            scope::set_trace_id(trace_id);
-                            let actor = self.actor.as_ref()?.as_actor()?;
-                            on_input_closed(&mut self.stage, actor);
-                            return None;
-                        }
-                    },
-                    option = self.sources.next(), if !self.sources.is_empty() => {
-                        let envelope = ward!(option, continue 'outer);
-                        break 'received envelope;
-                    },
-                }
-            };
+        if let Some(envelope) = self.stash.pop_unstashed() {
+            return Some(self.recv_unstashed(envelope).await);
+        }
 
-            if let Some(envelope) = self.post_recv(envelope) {
-                return Some(envelope);
-            }
+        if unlikely(self.stage == Stage::Draining) {
+            self.stage = Stage::Closed;
+            return None;
         }
+
+        let envelope = self.recv_fresh().await?;
+        self.stats.on_received_envelope(&envelope);
+        Some(envelope)
     }
 
     /// Receives the next envelope from the mailbox or sources without waiting.
@@ -578,53 +622,159 @@ impl<C, K> Context<C, K> {
     where
         C: 'static,
     {
-        #[allow(clippy::never_loop)] // false positive
-        loop {
-            self.budget.acquire().await;
+        if let Some(envelope) = self.stash.pop_unstashed() {
+            return Ok(self.recv_unstashed(envelope).await);
+        }
 
-            self.pre_recv();
+        if unlikely(self.stage == Stage::Draining) {
+            self.stage = Stage::Closed;
+            return Err(TryRecvError::Closed);
+        }
 
-            let envelope = 'received: {
-                let actor = ward!(
-                    self.actor.as_ref().and_then(|o| o.as_actor()),
-                    return Err(TryRecvError::Closed)
-                );
+        let envelope = self.try_recv_fresh().await?;
+        self.stats.on_received_envelope(&envelope);
+        Ok(envelope)
+    }
 
-                // TODO: poll mailbox and sources fairly.
-                match actor.try_recv() {
-                    Some(RecvResult::Data(envelope)) => {
-                        break 'received envelope;
-                    }
-                    Some(RecvResult::Closed(trace_id)) => {
-                        scope::set_trace_id(trace_id);
-                        on_input_closed(&mut self.stage, actor);
-                        return Err(TryRecvError::Closed);
-                    }
-                    None => {}
-                }
+    /// Waits for the first envelope matching the predicate.
+    /// Non-matching envelopes are kept in order and returned by subsequent
+    /// calls of [`Context::recv()`] and [`Context::try_recv()`].
+    /// If the mailbox is closed, `None` is returned.
+    ///
+    /// Skipped envelopes are handled by the context as usual, e.g. `Ping` is
+    /// answered and the config is updated on `UpdateConfig`. They aren't
+    /// limited by the stash capacity, so avoid waiting for too long.
+    ///
+    /// # Cancel safety
+    ///
+    /// This method is cancel safe. Skipped envelopes are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elfo_core as elfo;
+    /// # async fn exec(mut ctx: elfo::Context) {
+    /// # use elfo::message;
+    /// # #[message]
+    /// # struct Connected;
+    /// // Other messages will be received later.
+    /// if ctx.recv_matching(|e| e.is::<Connected>()).await.is_none() {
+    ///     return;
+    /// }
+    /// # }
+    /// ```
+    pub async fn recv_matching(
+        &mut self,
+        mut predicate: impl FnMut(&Envelope) -> bool,
+    ) -> Option<Envelope>
+    where
+        C: 'static,
+    {
+        if let Some(envelope) = self.stash.take_unstashed(&mut predicate) {
+            return Some(self.recv_unstashed(envelope).await);
+        }
 
-                if !self.sources.is_empty() {
-                    let envelope = poll_fn(|cx| match Pin::new(&mut self.sources).poll_next(cx) {
-                        Poll::Ready(Some(envelope)) => Poll::Ready(Some(envelope)),
-                        _ => Poll::Ready(None),
-                    })
-                    .await;
+        if unlikely(self.stage == Stage::Draining) {
+            return None;
+        }
 
-                    if let Some(envelope) = envelope {
-                        break 'received envelope;
+        loop {
+            let envelope = match self.recv_fresh().await {
+                Some(envelope) => envelope,
+                None => {
+                    // Allow `recv()` to return skipped envelopes before `None`.
+                    if self.stash.has_unstashed() {
+                        self.stage = Stage::Draining;
                     }
+                    return None;
                 }
-
-                self.stats.on_empty_mailbox();
-                return Err(TryRecvError::Empty);
             };
 
-            if let Some(envelope) = self.post_recv(envelope) {
-                return Ok(envelope);
+            if predicate(&envelope) {
+                self.stats.on_received_envelope(&envelope);
+                return Some(envelope);
             }
+
+            // Skipped envelopes are accounted once they are received.
+            self.stash.defer(envelope);
+        }
+    }
+
+    /// The same as [`Context::recv_matching()`], but waits only for the
+    /// provided duration. If no matching envelope is received in time,
+    /// `Err(RecvTimeoutError::Timeout)` is returned.
+    pub async fn recv_matching_timeout(
+        &mut self,
+        timeout: Duration,
+        predicate: impl FnMut(&Envelope) -> bool,
+    ) -> Result<Envelope, RecvTimeoutError>
+    where
+        C: 'static,
+    {
+        match tokio::time::timeout(timeout, self.recv_matching(predicate)).await {
+            Ok(Some(envelope)) => Ok(envelope),
+            Ok(None) => Err(RecvTimeoutError::Closed),
+            Err(_) => Err(RecvTimeoutError::Timeout),
         }
     }
 
+    /// Defers the envelope until [`Context::unstash_all()`] is called.
+    /// Useful to postpone messages that cannot be handled in the current
+    /// state of the actor, e.g. during multi-phase protocols.
+    ///
+    /// The number of stashed envelopes is limited by the
+    /// `system.mailbox.stash_capacity` config parameter. If the stash is full,
+    /// the envelope is returned back inside the error.
+    ///
+    /// Stashed requests aren't answered until they are handled or dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use elfo_core as elfo;
+    /// # async fn exec(mut ctx: elfo::Context) {
+    /// # use elfo::{message, msg};
+    /// # #[message]
+    /// # struct Connected;
+    /// # #[message]
+    /// # struct Query;
+    /// let mut connected = false;
+    ///
+    /// while let Some(envelope) = ctx.recv().await {
+    ///     if !connected && envelope.is::<Query>() {
+    ///         // Drop the query if there are too many of them.
+    ///         let _ = ctx.stash(envelope);
+    ///         continue;
+    ///     }
+    ///
+    ///     msg!(match envelope {
+    ///         Connected => {
+    ///             connected = true;
+    ///             ctx.unstash_all();
+    ///         }
+    ///         Query => { /* ... */ }
+    ///     });
+    /// }
+    /// # }
+    /// ```
+    #[allow(clippy::result_large_err)]
+    pub fn stash(&mut self, envelope: Envelope) -> Result<(), StashError<Envelope>> {
+        let capacity = self
+            .actor
+            .as_ref()
+            .and_then(|o| o.as_actor())
+            .map_or(0, |actor| actor.stash_capacity());
+
+        self.stash.push(envelope, capacity).map_err(StashError)
+    }
+
+    /// Returns all stashed envelopes back. They are received by
+    /// [`Context::recv()`] and [`Context::try_recv()`] in the original
+    /// order before new envelopes from the mailbox and sources.
+    pub fn unstash_all(&mut self) {
+        self.stash.unstash_all();
+    }
+
     /// Retrieves information related to the start of the actor.
     ///
     /// # Panics
@@ -648,6 +798,9 @@ impl<C, K> Context<C, K> {
     ///     ActorStartCause::Restarted => {
     ///         // The actor started due to the restart policy.
     ///     }
+    ///     ActorStartCause::Reactivated => {
+    ///         // The actor started in response to a message after passivation.
+    ///     }
     ///     _ => {}
     /// }
     /// # }
@@ -658,6 +811,128 @@ impl<C, K> Context<C, K> {
             .expect("start_info is not available for a group context")
     }
 
+    /// Receives the next envelope from the mailbox or sources,
+    /// ignoring unstashed envelopes. The envelope isn't accounted in stats.
+    async fn recv_fresh(&mut self) -> Option<Envelope>
+    where
+        C: 'static,
+    {
+        let actor = self.actor.as_ref().and_then(|o| o.as_actor());
+        let mut idle_timeout = actor.and_then(|actor| actor.idle_timeout());
+        let idle = tokio::time::sleep(idle_timeout.unwrap_or(Duration::MAX));
+        pin_mut!(idle);
+
+        'outer: loop {
+            // TODO: reset if the mailbox is empty.
+            self.budget.acquire().await;
+
+            self.pre_recv();
+
+            let envelope = 'received: {
+                let actor = self.actor.as_ref()?.as_actor()?;
+                let mailbox_fut = actor.recv();
+                pin_mut!(mailbox_fut);
+                let expired_fut = actor.request_table().expired();
+                pin_mut!(expired_fut);
+
+                tokio::select! {
+                    result = mailbox_fut => match result {
+                        RecvResult::Data(envelope) => {
+                            break 'received envelope;
+                        },
+                        RecvResult::Stale(envelope) => {
+                            drop_stale(envelope, &mut self.budget, &self.stats);
+                            continue 'outer;
+                        },
+                        RecvResult::Closed(trace_id) => {
+                            scope::set_trace_id(trace_id);
+                            let actor = self.actor.as_ref()?.as_actor()?;
+                            on_input_closed(&mut self.stage, actor);
+                            return None;
+                        }
+                    },
+                    option = self.sources.next(), if !self.sources.is_empty() => {
+                        let envelope = ward!(option, continue 'outer);
+                        break 'received envelope;
+                    },
+                    _ = expired_fut => {
+                        // Responses are delivered to the mailbox.
+                        actor.request_table().time_out_expired(&self.book);
+                        continue 'outer;
+                    },
+                    _ = &mut idle, if idle_timeout.is_some() => {
+                        info!(idle_timeout = ?idle_timeout.take(), "passivating");
+                        self.actor.as_ref()?.as_actor()?.passivate();
+                        continue 'outer;
+                    },
+                }
+            };
+
+            if let Some(envelope) = self.post_recv(envelope) {
+                return Some(envelope);
+            }
+        }
+    }
+
+    /// Receives the next envelope from the mailbox or sources without waiting,
+    /// ignoring unstashed envelopes. The envelope isn't accounted in stats.
+    async fn try_recv_fresh(&mut self) -> Result<Envelope, TryRecvError>
+    where
+        C: 'static,
+    {
+        'outer: loop {
+            self.budget.acquire().await;
+
+            self.pre_recv();
+
+            let envelope = 'received: {
+                let actor = ward!(
+                    self.actor.as_ref().and_then(|o| o.as_actor()),
+                    return Err(TryRecvError::Closed)
+                );
+
+                // Responses are delivered to the mailbox.
+                actor.request_table().time_out_expired(&self.book);
+
+                // TODO: poll mailbox and sources fairly.
+                match actor.try_recv() {
+                    Some(RecvResult::Data(envelope)) => {
+                        break 'received envelope;
+                    }
+                    Some(RecvResult::Stale(envelope)) => {
+                        drop_stale(envelope, &mut self.budget, &self.stats);
+                        continue 'outer;
+                    }
+                    Some(RecvResult::Closed(trace_id)) => {
+                        scope::set_trace_id(trace_id);
+                        on_input_closed(&mut self.stage, actor);
+                        return Err(TryRecvError::Closed);
+                    }
+                    None => {}
+                }
+
+                if !self.sources.is_empty() {
+                    let envelope = poll_fn(|cx| match Pin::new(&mut self.sources).poll_next(cx) {
+                        Poll::Ready(Some(envelope)) => Poll::Ready(Some(envelope)),
+                        _ => Poll::Ready(None),
+                    })
+                    .await;
+
+                    if let Some(envelope) = envelope {
+                        break 'received envelope;
+                    }
+                }
+
+                self.stats.on_empty_mailbox();
+                return Err(TryRecvError::Empty);
+            };
+
+            if let Some(envelope) = self.post_recv(envelope) {
+                return Ok(envelope);
+            }
+        }
+    }
+
     fn pre_recv(&mut self) {
         self.stats.on_recv();
 
@@ -710,7 +985,11 @@ impl<C, K> Context<C, K> {
             self.set_status(ActorStatus::TERMINATING);
         }
 
-        self.stats.on_received_envelope(&envelope);
+        // Other envelopes are accounted by callers, because skipped ones
+        // are accounted only once they are received.
+        if envelope.is::<messages::Ping>() {
+            self.stats.on_received_envelope(&envelope);
+        }
 
         msg!(match envelope {
             (messages::Ping, token) => {
@@ -721,6 +1000,105 @@ impl<C, K> Context<C, K> {
         })
     }
 
+    async fn recv_unstashed(&mut self, envelope: Envelope) -> Envelope {
+        self.budget.acquire().await;
+        self.stats.on_recv();
+        self.budget.decrement();
+
+        scope::set_trace_id(envelope.trace_id());
+
+        // The envelope has already been dumped when it was received.
+        trace!("< {:?} (unstashed)", envelope.message());
+
+        self.stats.on_received_envelope(&envelope);
+        envelope
+    }
+
+    /// Monitors the specified actor: `messages::Down` with its final status
+    /// is delivered into the mailbox once the actor terminates or fails.
+    ///
+    /// Only local actors can be monitored. If the actor has already finished,
+    /// `Down` is delivered immediately.
+    ///
+    /// # Example
+    /// ```
+    /// # use elfo_core as elfo;
+    /// # async fn exec(mut ctx: elfo::Context, addr: elfo::Addr) {
+    /// # use elfo::{msg, messages::Down};
+    /// ctx.monitor(addr);
+    ///
+    /// while let Some(envelope) = ctx.recv().await {
+    ///     msg!(match envelope {
+    ///         Down { addr, status, .. } => {
+    ///             // The monitored actor is gone.
+    ///         }
+    ///     });
+    /// }
+    /// # }
+    /// ```
+    pub fn monitor(&self, addr: Addr) {
+        let object = self.book.get(addr);
+        let status = match object.as_ref().and_then(|o| o.as_actor()) {
+            Some(actor) => ward!(actor.add_monitor(self.actor_addr).err()),
+            None => ActorStatus::TERMINATED.with_details("no actor"),
+        };
+
+        let down = messages::Down {
+            addr: addr.into(),
+            status,
+        };
+        let _ = self.try_send_to(self.actor_addr, down);
+    }
+
+    /// Stops monitoring the specified actor, see [`Context::monitor()`].
+    /// `messages::Down` sent before can still be received.
+    pub fn demonitor(&self, addr: Addr) {
+        let object = ward!(self.book.get(addr));
+        let actor = ward!(object.as_actor());
+        actor.remove_monitor(self.actor_addr);
+    }
+
+    /// Links the current actor with the specified one, so they fail together:
+    /// if one of them fails, the mailbox of the other one is closed and it's
+    /// considered failed after exiting. Normal termination doesn't affect
+    /// linked actors.
+    ///
+    /// Only local actors can be linked. If the actor has already failed or
+    /// doesn't exist, the current actor fails.
+    pub fn link(&self, addr: Addr) {
+        let this = ward!(self.actor.as_ref().and_then(|o| o.as_actor()));
+
+        let object = self.book.get(addr);
+        let result = match object.as_ref().and_then(|o| o.as_actor()) {
+            Some(actor) => actor.add_link(self.actor_addr),
+            None => Err(ActorStatus::FAILED.with_details("no actor")),
+        };
+
+        match result {
+            Ok(()) => {
+                let _ = this.add_link(addr);
+            }
+            Err(status) if status.is_failed() => {
+                this.fail_linked(format!("linked actor {addr} failed"));
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Removes the link between the current actor and the specified one,
+    /// see [`Context::link()`]. Links are also removed once one of actors
+    /// terminates.
+    ///
+    /// If the actor has already failed, the current actor can still fail.
+    pub fn unlink(&self, addr: Addr) {
+        let this = ward!(self.actor.as_ref().and_then(|o| o.as_actor()));
+        this.remove_link(addr);
+
+        let object = ward!(self.book.get(addr));
+        let actor = ward!(object.as_actor());
+        actor.remove_link(self.actor_addr);
+    }
+
     /// This is a part of private API for now.
     /// We should provide a way to handle it asynchronous.
     #[doc(hidden)]
@@ -761,6 +1139,7 @@ impl<C, K> Context<C, K> {
             stage: self.stage,
             stats: Stats::empty(),
             budget: self.budget.clone(),
+            stash: Stash::default(),
         }
     }
 
@@ -784,6 +1163,7 @@ impl<C, K> Context<C, K> {
             stage: self.stage,
             stats: self.stats,
             budget: self.budget,
+            stash: self.stash,
         }
     }
 
@@ -819,6 +1199,7 @@ impl<C, K> Context<C, K> {
             stage: self.stage,
             stats: self.stats,
             budget: self.budget,
+            stash: self.stash,
         }
     }
 }
@@ -839,6 +1220,25 @@ fn on_input_closed(stage: &mut Stage, actor: &Actor) {
     trace!("input closed");
 }
 
+#[cold]
+fn drop_stale(envelope: Envelope, budget: &mut Budget, stats: &Stats) {
+    budget.decrement();
+
+    scope::set_trace_id(envelope.trace_id());
+
+    let message = envelope.message();
+    trace!("< {:?} (stale, dropped)", message);
+    if let Some(permit) = DUMPER.acquire_m(message) {
+        let kind = envelope.message_kind();
+        permit.record(Dump::message(message, kind, Direction::In));
+    }
+
+    stats.on_stale_envelope(&envelope);
+
+    // Dropping a request leads to `RequestError::Failed` on the sender side.
+    drop(envelope);
+}
+
 fn addrs_with_envelope(
     envelope: Envelope,
     addrs: &[Addr],
@@ -882,6 +1282,7 @@ impl Context {
             stage: Stage::PreRecv,
             stats: Stats::empty(),
             budget: Budget::default(),
+            stash: Stash::default(),
         }
     }
 }
@@ -902,6 +1303,7 @@ impl<C, K: Clone> Clone for Context<C, K> {
             stage: self.stage,
             stats: Stats::empty(),
             budget: self.budget.clone(),
+            stash: Stash::default(),
         }
     }
 }
@@ -911,11 +1313,18 @@ pub struct RequestBuilder<'c, C, K, R, M> {
     context: &'c Context<C, K>,
     request: R,
     to: Option<Addr>,
-    marker: PhantomData<M>,
+    timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
+    hedge: Option<Duration>,
+    mode: M,
 }
 
 pub struct Any;
 pub struct All;
+pub struct First(usize);
+pub struct Quorum(usize);
+/// `Any` with retries or hedges, the mode cannot be changed anymore.
+pub struct Resilient;
 
 impl<'c, C, K, R> RequestBuilder<'c, C, K, R, Any> {
     fn new(context: &'c Context<C, K>, request: R) -> Self {
@@ -923,18 +1332,102 @@ impl<'c, C, K, R> RequestBuilder<'c, C, K, R, Any> {
             context,
             request,
             to: None,
-            marker: PhantomData,
+            timeout: None,
+            retry: None,
+            hedge: None,
+            mode: Any,
         }
     }
 
     #[inline]
     pub fn all(self) -> RequestBuilder<'c, C, K, R, All> {
-        RequestBuilder {
-            context: self.context,
-            request: self.request,
-            to: self.to,
-            marker: PhantomData,
-        }
+        self.with_mode(All)
+    }
+
+    /// Waits only for the first `n` successful responses, others are
+    /// discarded. Useful for requests to groups with many actors.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is zero.
+    #[inline]
+    #[track_caller]
+    pub fn first(self, n: usize) -> RequestBuilder<'c, C, K, R, First> {
+        assert_ne!(n, 0, "the number of responses must be non-zero");
+        self.with_mode(First(n))
+    }
+
+    /// Waits only for `n` successful responses, others are discarded.
+    /// Unlike [`RequestBuilder::first()`], fails if fewer actors succeed.
+    /// Useful to get majority acknowledgements from replicas.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is zero.
+    #[inline]
+    #[track_caller]
+    pub fn quorum(self, n: usize) -> RequestBuilder<'c, C, K, R, Quorum> {
+        assert_ne!(n, 0, "the number of responses must be non-zero");
+        self.with_mode(Quorum(n))
+    }
+
+    /// Retries the request according to the policy if it fails or times
+    /// out. Ignored requests aren't retried. The timeout set by
+    /// [`RequestBuilder::timeout()`] is applied to each attempt.
+    ///
+    /// All attempts have the same trace id. Retries are logged and counted
+    /// by the `elfo_request_retries_total` metric.
+    ///
+    /// Use it only for idempotent requests. Only [`RequestBuilder::resolve()`]
+    /// is available afterwards.
+    #[inline]
+    pub fn retry(self, policy: RetryPolicy) -> RequestBuilder<'c, C, K, R, Resilient> {
+        self.with_mode(Resilient).retry(policy)
+    }
+
+    /// Sends a copy of the request if no response is received in `after`.
+    /// The copy is routed as usual, so it reaches another actor only if the
+    /// router chooses one. The first successful response is returned.
+    ///
+    /// The copy has the same trace id. Hedges are logged and counted
+    /// by the `elfo_request_hedges_total` metric.
+    ///
+    /// Use it only for idempotent requests. Only [`RequestBuilder::resolve()`]
+    /// is available afterwards.
+    ///
+    /// # Panics
+    ///
+    /// If the request is created by [`Context::request_to()`], because the
+    /// copy would reach the same actor.
+    #[inline]
+    #[track_caller]
+    pub fn hedge(self, after: Duration) -> RequestBuilder<'c, C, K, R, Resilient> {
+        self.with_mode(Resilient).hedge(after)
+    }
+}
+
+impl<'c, C, K, R> RequestBuilder<'c, C, K, R, Resilient> {
+    /// See [`RequestBuilder::retry()`] of `any` requests.
+    #[inline]
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// See [`RequestBuilder::hedge()`] of `any` requests.
+    ///
+    /// # Panics
+    ///
+    /// If the request is created by [`Context::request_to()`].
+    #[inline]
+    #[track_caller]
+    pub fn hedge(mut self, after: Duration) -> Self {
+        assert!(
+            self.to.is_none(),
+            "requests to a specific address cannot be hedged"
+        );
+        self.hedge = Some(after);
+        self
     }
 }
 
@@ -945,75 +1438,274 @@ impl<'c, C, K, R, M> RequestBuilder<'c, C, K, R, M> {
         self.to = Some(addr);
         self
     }
+
+    /// Limits the time of waiting for responses, including the time of
+    /// sending the request. Missing responses are replaced with
+    /// `RequestError::TimedOut`, late ones are dropped and counted by the
+    /// `elfo_late_responses_total` metric.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    fn with_mode<M1>(self, mode: M1) -> RequestBuilder<'c, C, K, R, M1> {
+        RequestBuilder {
+            context: self.context,
+            request: self.request,
+            to: self.to,
+            timeout: self.timeout,
+            retry: self.retry,
+            hedge: self.hedge,
+            mode,
+        }
+    }
 }
 
-// TODO: add `pub async fn id() { ... }`
-impl<'c, C: 'static, K, R: Request> RequestBuilder<'c, C, K, R, Any> {
-    /// Waits for the response.
-    pub async fn resolve(self) -> Result<R::Response, RequestError> {
+impl<'c, C: 'static, K, R: Request, M> RequestBuilder<'c, C, K, R, M> {
+    async fn do_resolve(self, mode: RequestMode) -> Result<Responses, RequestError> {
         // TODO: cache `OwnedEntry`?
         let this = self.context.actor_addr;
         let object = self.context.book.get_owned(this).expect("invalid addr");
         let actor = object.as_actor().expect("can be called only on actors");
-        let token =
-            actor
-                .request_table()
-                .new_request(self.context.book.clone(), scope::trace_id(), false);
+        let trace_id = scope::trace_id();
+        let token = actor
+            .request_table()
+            .new_request(self.context.book.clone(), trace_id, mode);
         let request_id = token.request_id();
-        let kind = MessageKind::RequestAny(token);
+        let kind = match mode {
+            RequestMode::Any => MessageKind::RequestAny(token),
+            RequestMode::All | RequestMode::First(_) => MessageKind::RequestAll(token),
+        };
 
-        let res = if let Some(recipient) = self.to {
-            self.context.do_send_to(recipient, self.request, kind).await
-        } else {
-            self.context.do_send(self.request, kind).await
+        // Only `any` requests to groups can be hedged.
+        let hedge = self.hedge.map(|after| (after, self.request.clone()));
+
+        let fut = async {
+            let res = if let Some(recipient) = self.to {
+                self.context.do_send_to(recipient, self.request, kind).await
+            } else {
+                self.context.do_send(self.request, kind).await
+            };
+
+            if res.is_err() {
+                actor.request_table().cancel_request(request_id);
+                return Err(RequestError::Failed);
+            }
+
+            let wait = actor.request_table().wait(request_id);
+            pin_mut!(wait);
+
+            let (after, copy) = ward!(hedge, return Ok(wait.await));
+
+            tokio::select! {
+                responses = &mut wait => return Ok(responses),
+                _ = tokio::time::sleep(after) => {}
+            }
+
+            let book = self.context.book.clone();
+            let token = actor
+                .request_table()
+                .hedge_request(book, request_id, trace_id);
+
+            if let Some(token) = token {
+                info!(?after, "no response, hedging the request");
+                increment_counter!("elfo_request_hedges_total");
+
+                // If the copy cannot be sent, the token is dropped,
+                // so `RequestError::Failed` is counted as a response.
+                let kind = MessageKind::RequestAny(token);
+                let _ = self.context.do_send(copy, kind).await;
+            }
+
+            Ok(wait.await)
         };
 
-        if res.is_err() {
-            actor.request_table().cancel_request(request_id);
-            return Err(RequestError::Failed);
+        match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, fut).await {
+                Ok(res) => res,
+                Err(_) => Ok(actor.request_table().time_out_request(request_id)),
+            },
+            None => fut.await,
         }
-
-        let mut responses = actor.request_table().wait(request_id).await;
-        debug_assert_eq!(responses.len(), 1);
-        prepare_response::<R>(responses.pop().expect("missing response"))
     }
-}
 
-impl<'c, C: 'static, K, R: Request> RequestBuilder<'c, C, K, R, All> {
-    /// Waits for the responses.
-    pub async fn resolve(self) -> Vec<Result<R::Response, RequestError>> {
+    async fn do_id(self, mode: RequestMode) -> RequestId {
         // TODO: cache `OwnedEntry`?
         let this = self.context.actor_addr;
         let object = self.context.book.get_owned(this).expect("invalid addr");
         let actor = object.as_actor().expect("can be called only on actors");
-        let token =
-            actor
-                .request_table()
-                .new_request(self.context.book.clone(), scope::trace_id(), true);
+        // The deadline is processed by the actor itself in `recv()`.
+        let deadline = self
+            .timeout
+            .map(|timeout| tokio::time::Instant::now() + timeout);
+        let token = actor.request_table().new_async_request(
+            self.context.book.clone(),
+            scope::trace_id(),
+            mode,
+            deadline,
+        );
         let request_id = token.request_id();
-        let kind = MessageKind::RequestAll(token);
+        let kind = match mode {
+            RequestMode::Any => MessageKind::RequestAny(token),
+            RequestMode::All | RequestMode::First(_) => MessageKind::RequestAll(token),
+        };
 
-        let res = if let Some(recipient) = self.to {
+        // If the request cannot be sent, the token is dropped,
+        // so `RequestError::Failed` is delivered as a response.
+        let _ = if let Some(recipient) = self.to {
             self.context.do_send_to(recipient, self.request, kind).await
         } else {
             self.context.do_send(self.request, kind).await
         };
 
-        if res.is_err() {
-            actor.request_table().cancel_request(request_id);
-            return vec![Err(RequestError::Failed)];
+        request_id
+    }
+}
+
+impl<'c, C: 'static, K, R: Request> RequestBuilder<'c, C, K, R, Any> {
+    /// Sends the request and returns its id without waiting for the response.
+    ///
+    /// The response is delivered to the mailbox as [`RequestResolved`] with
+    /// the same `request_id`. Use [`RequestResolved::into_response()`] to get
+    /// the response.
+    ///
+    /// [`RequestResolved`]: crate::messages::RequestResolved
+    /// [`RequestResolved::into_response()`]: crate::messages::RequestResolved::into_response
+    pub async fn id(self) -> RequestId {
+        self.do_id(RequestMode::Any).await
+    }
+
+    /// Waits for the response.
+    pub async fn resolve(self) -> Result<R::Response, RequestError> {
+        let mut responses = self.do_resolve(RequestMode::Any).await?;
+        debug_assert_eq!(responses.len(), 1);
+        prepare_response::<R>(responses.pop().expect("missing response"))
+    }
+}
+
+impl<'c, C: 'static, K, R: Request> RequestBuilder<'c, C, K, R, Resilient> {
+    /// Waits for the response, retrying and hedging the request if needed.
+    pub async fn resolve(self) -> Result<R::Response, RequestError> {
+        let policy = self.retry;
+        let mut backoff = RestartBackoff::default();
+        let mut attempt = 1;
+
+        loop {
+            let this = RequestBuilder {
+                context: self.context,
+                request: self.request.clone(),
+                to: self.to,
+                timeout: self.timeout,
+                retry: None,
+                hedge: self.hedge,
+                mode: Any,
+            };
+
+            let error = match this.resolve().await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            // The recipient has got the request, so it isn't retried.
+            if matches!(error, RequestError::Ignored) {
+                return Err(error);
+            }
+
+            let policy = ward!(policy, return Err(error));
+            let delay = ward!(backoff.next(&policy.params), return Err(error));
+            attempt += 1;
+
+            info!(%error, attempt, ?delay, "request failed, retrying");
+            increment_counter!("elfo_request_retries_total");
+            tokio::time::sleep(delay).await;
         }
+    }
+}
 
-        actor
-            .request_table()
-            .wait(request_id)
-            .await
+impl<'c, C: 'static, K, R: Request> RequestBuilder<'c, C, K, R, All> {
+    /// Sends the request and returns its id without waiting for responses.
+    ///
+    /// Responses are delivered to the mailbox as [`RequestResolved`] with
+    /// the same `request_id` once all of them are received. Use
+    /// [`RequestResolved::into_responses()`] to get the responses.
+    ///
+    /// [`RequestResolved`]: crate::messages::RequestResolved
+    /// [`RequestResolved::into_responses()`]: crate::messages::RequestResolved::into_responses
+    pub async fn id(self) -> RequestId {
+        self.do_id(RequestMode::All).await
+    }
+
+    /// Waits for the responses.
+    pub async fn resolve(self) -> Vec<Result<R::Response, RequestError>> {
+        match self.do_resolve(RequestMode::All).await {
+            Ok(responses) => responses.into_iter().map(prepare_response::<R>).collect(),
+            Err(err) => vec![Err(err)],
+        }
+    }
+}
+
+impl<'c, C: 'static, K, R: Request> RequestBuilder<'c, C, K, R, First> {
+    /// Sends the request and returns its id without waiting for responses.
+    /// Responses are delivered to the mailbox as `RequestResolved` once
+    /// enough successful ones are received.
+    pub async fn id(self) -> RequestId {
+        let n = self.mode.0;
+        self.do_id(RequestMode::First(n)).await
+    }
+
+    /// Waits for the first `n` successful responses.
+    /// If fewer actors succeed, all successful responses are returned.
+    pub async fn resolve(self) -> Vec<R::Response> {
+        let n = self.mode.0;
+        let responses = ward!(
+            self.do_resolve(RequestMode::First(n)).await.ok(),
+            return Vec::new()
+        );
+
+        responses
             .into_iter()
-            .map(prepare_response::<R>)
+            .filter_map(|response| prepare_response::<R>(response).ok())
             .collect()
     }
 }
 
+impl<'c, C: 'static, K, R: Request> RequestBuilder<'c, C, K, R, Quorum> {
+    /// Sends the request and returns its id without waiting for responses.
+    /// Responses are delivered to the mailbox as `RequestResolved` once
+    /// enough successful ones are received.
+    pub async fn id(self) -> RequestId {
+        let n = self.mode.0;
+        self.do_id(RequestMode::First(n)).await
+    }
+
+    /// Waits for `n` successful responses.
+    ///
+    /// If fewer actors succeed, an error is returned. Priority:
+    /// `Err(TimedOut)` > `Err(Ignored)` > `Err(Failed)`.
+    pub async fn resolve(self) -> Result<Vec<R::Response>, RequestError> {
+        let n = self.mode.0;
+        let responses = self.do_resolve(RequestMode::First(n)).await?;
+
+        let mut successes = Vec::with_capacity(n);
+        let mut error = RequestError::Failed;
+
+        for response in responses {
+            match prepare_response::<R>(response) {
+                Ok(response) => successes.push(response),
+                Err(err) if !error.is_timed_out() && !err.is_failed() => error = err,
+                Err(_) => {}
+            }
+        }
+
+        if successes.len() >= n {
+            Ok(successes)
+        } else {
+            Err(error)
+        }
+    }
+}
+
 fn prepare_response<R: Request>(
     response: Result<Envelope, RequestError>,
 ) -> Result<R::Response, RequestError> {
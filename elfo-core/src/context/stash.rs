@@ -0,0 +1,82 @@
+use std::collections::VecDeque;
+
+use metrics::{decrement_gauge, increment_gauge};
+use parking_lot::Mutex;
+
+use crate::envelope::Envelope;
+
+/// Envelopes deferred by `Context::stash()`.
+///
+/// `Envelope` isn't `Sync`, so a mutex is used to keep `Context` `Sync`.
+/// It's never locked, because all methods require `&mut self`.
+#[derive(Default)]
+pub(crate) struct Stash(Mutex<Inner>);
+
+#[derive(Default)]
+struct Inner {
+    stashed: VecDeque<Envelope>,
+    /// Unstashed and deferred envelopes,
+    /// returned by `recv()` and `try_recv()` before the mailbox.
+    unstashed: VecDeque<Envelope>,
+}
+
+impl Stash {
+    /// Returns the envelope back if the stash is full.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn push(&mut self, envelope: Envelope, capacity: usize) -> Result<(), Envelope> {
+        let inner = self.0.get_mut();
+
+        if inner.stashed.len() >= capacity {
+            return Err(envelope);
+        }
+
+        inner.stashed.push_back(envelope);
+        increment_gauge!("elfo_stashed_messages", 1.);
+        Ok(())
+    }
+
+    pub(crate) fn unstash_all(&mut self) {
+        let inner = self.0.get_mut();
+
+        if inner.stashed.is_empty() {
+            return;
+        }
+
+        decrement_gauge!("elfo_stashed_messages", inner.stashed.len() as f64);
+        inner.unstashed.append(&mut inner.stashed);
+    }
+
+    pub(crate) fn pop_unstashed(&mut self) -> Option<Envelope> {
+        self.0.get_mut().unstashed.pop_front()
+    }
+
+    /// Removes the first unstashed envelope matching the predicate.
+    pub(crate) fn take_unstashed(
+        &mut self,
+        predicate: &mut impl FnMut(&Envelope) -> bool,
+    ) -> Option<Envelope> {
+        let unstashed = &mut self.0.get_mut().unstashed;
+        let index = unstashed.iter().position(predicate)?;
+        unstashed.remove(index)
+    }
+
+    /// Defers the envelope skipped by `Context::recv_matching()`.
+    /// Such envelopes aren't limited by the capacity.
+    pub(crate) fn defer(&mut self, envelope: Envelope) {
+        self.0.get_mut().unstashed.push_back(envelope);
+    }
+
+    pub(crate) fn has_unstashed(&mut self) -> bool {
+        !self.0.get_mut().unstashed.is_empty()
+    }
+}
+
+impl Drop for Stash {
+    fn drop(&mut self) {
+        let inner = self.0.get_mut();
+
+        if !inner.stashed.is_empty() {
+            decrement_gauge!("elfo_stashed_messages", inner.stashed.len() as f64);
+        }
+    }
+}
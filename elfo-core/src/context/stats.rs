@@ -48,6 +48,12 @@ impl Stats {
         self.in_handling = Some(InHandling::new(envelope.message().labels(), now));
     }
 
+    pub(super) fn on_stale_envelope(&self, envelope: &Envelope) {
+        let recorder = ward!(metrics::try_recorder());
+        let key = Key::from_static_parts("elfo_stale_messages_total", envelope.message().labels());
+        recorder.increment_counter(&key, 1);
+    }
+
     pub(super) fn on_empty_mailbox(&mut self) {
         debug_assert!(self.in_handling.is_none());
 
@@ -0,0 +1,43 @@
+use dashmap::DashMap;
+use fxhash::FxBuildHasher;
+
+use elfo_utils::{RateLimit, RateLimiter};
+
+use crate::{addr::Addr, message::Message};
+
+// TODO: make it configurable.
+const MAX_RATE_PER_TYPE: u64 = 100;
+
+/// The deadletter group, see `Local::deadletter()`.
+pub(crate) struct DeadLetters {
+    addr: Addr,
+    /// Rate limiters per `(protocol, name)` of messages.
+    limiters: DashMap<(&'static str, &'static str), RateLimiter, FxBuildHasher>,
+}
+
+impl DeadLetters {
+    pub(crate) fn new(addr: Addr) -> Self {
+        Self {
+            addr,
+            limiters: DashMap::default(),
+        }
+    }
+
+    pub(crate) fn addr(&self) -> Addr {
+        self.addr
+    }
+
+    /// Returns `false` if too many messages of this type are undeliverable.
+    pub(crate) fn acquire(&self, message: &impl Message) -> bool {
+        let key = (message.protocol(), message.name());
+
+        if let Some(limiter) = self.limiters.get(&key) {
+            return limiter.acquire();
+        }
+
+        self.limiters
+            .entry(key)
+            .or_insert_with(|| RateLimiter::new(RateLimit::Rps(MAX_RATE_PER_TYPE)))
+            .acquire()
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use arc_swap::ArcSwap;
 use smallvec::SmallVec;
 
 use crate::{envelope::Envelope, Addr};
@@ -7,31 +8,104 @@ use crate::{envelope::Envelope, Addr};
 const OPTIMAL_COUNT: usize = 5;
 type Addrs = SmallVec<[Addr; OPTIMAL_COUNT]>;
 
+#[cfg(feature = "network")]
+type Filter = Arc<dyn Fn(&Envelope, &mut Addrs) + Send + Sync>;
+type LocalFilter = Arc<dyn Fn(&Envelope) -> bool + Send + Sync>;
+
 // Actually, it's a private type, `pub` is for `Destination` only.
+//
+// Routes are shared by all contexts of the group and can be changed at runtime.
+// Changes replace the whole list, so in-flight `filter()` calls use old routes.
 #[derive(Default, Clone)]
 pub struct Demux {
-    #[allow(clippy::type_complexity)]
-    filter: Option<Arc<dyn Fn(&Envelope, &mut Addrs) + Send + Sync>>,
+    routes: Arc<ArcSwap<Vec<Route>>>,
+}
+
+#[derive(Clone)]
+enum Route {
+    Local {
+        to: Addr,
+        filter: LocalFilter,
+    },
+    #[cfg(feature = "network")]
+    Custom(Filter),
 }
 
 impl Demux {
-    pub(crate) fn append(&mut self, f: impl Fn(&Envelope, &mut Addrs) + Send + Sync + 'static) {
-        self.filter = Some(if let Some(prev) = self.filter.take() {
-            Arc::new(move |envelope, addrs| {
-                prev(envelope, addrs);
-                f(envelope, addrs);
-            })
-        } else {
-            Arc::new(f)
+    #[cfg(feature = "network")]
+    pub(crate) fn append(&self, f: impl Fn(&Envelope, &mut Addrs) + Send + Sync + 'static) {
+        self.push(Route::Custom(Arc::new(f)));
+    }
+
+    pub(crate) fn append_local(
+        &self,
+        to: Addr,
+        filter: impl Fn(&Envelope) -> bool + Send + Sync + 'static,
+    ) {
+        let filter = Arc::new(filter);
+        self.push(Route::Local { to, filter });
+    }
+
+    /// Removes all routes to the local group.
+    /// Returns `false` if there are no such routes.
+    pub(crate) fn remove_local(&self, to: Addr) -> bool {
+        self.update_local(to, |routes| routes.retain(|route| !route.is_local_to(to)))
+    }
+
+    /// Redirects all routes to the `old` local group to the `new` one,
+    /// keeping filters. Returns `false` if there are no such routes.
+    pub(crate) fn replace_local(&self, old: Addr, new: Addr) -> bool {
+        self.update_local(old, |routes| {
+            for route in routes {
+                match route {
+                    Route::Local { to, .. } if *to == old => *to = new,
+                    _ => {}
+                }
+            }
         })
     }
 
     // TODO: return an iterator?
     pub(crate) fn filter(&self, envelope: &Envelope) -> Addrs {
         let mut addrs = Addrs::new();
-        if let Some(filter) = &self.filter {
-            (filter)(envelope, &mut addrs);
+        for route in self.routes.load().iter() {
+            match route {
+                Route::Local { to, filter } => {
+                    if filter(envelope) {
+                        addrs.push(*to);
+                    }
+                }
+                #[cfg(feature = "network")]
+                Route::Custom(filter) => filter(envelope, &mut addrs),
+            }
         }
         addrs
     }
+
+    fn push(&self, route: Route) {
+        self.routes.rcu(|routes| {
+            let mut routes = (**routes).clone();
+            routes.push(route.clone());
+            routes
+        });
+    }
+
+    fn update_local(&self, to: Addr, f: impl Fn(&mut Vec<Route>)) -> bool {
+        if !self.routes.load().iter().any(|route| route.is_local_to(to)) {
+            return false;
+        }
+
+        self.routes.rcu(|routes| {
+            let mut routes = (**routes).clone();
+            f(&mut routes);
+            routes
+        });
+        true
+    }
+}
+
+impl Route {
+    fn is_local_to(&self, addr: Addr) -> bool {
+        matches!(self, Route::Local { to, .. } if *to == addr)
+    }
 }
@@ -4,6 +4,7 @@ use std::{
 };
 
 use derive_more::{Display, Error};
+use serde::{Deserialize, Serialize};
 
 #[derive(Error)]
 #[non_exhaustive]
@@ -129,6 +130,10 @@ impl<T> TrySendError<T> {
 }
 
 #[derive(Debug, Display, Error)]
+#[display(fmt = "stash full")]
+pub struct StashError<T>(#[error(not(source))] pub T);
+
+#[derive(Debug, Clone, Display, Error, Serialize, Deserialize)]
 pub enum RequestError {
     /// Receiver hasn't got the request.
     #[display(fmt = "request failed")]
@@ -136,6 +141,10 @@ pub enum RequestError {
     /// Receiver has got the request, but ignored it.
     #[display(fmt = "request ignored")]
     Ignored,
+    /// Receiver hasn't responded in the time set by
+    /// `RequestBuilder::timeout()`.
+    #[display(fmt = "request timed out")]
+    TimedOut,
 }
 
 impl RequestError {
@@ -150,6 +159,12 @@ impl RequestError {
     pub fn is_ignored(&self) -> bool {
         matches!(self, Self::Ignored)
     }
+
+    /// Returns whether the error is the `TimedOut` variant.
+    #[inline]
+    pub fn is_timed_out(&self) -> bool {
+        matches!(self, Self::TimedOut)
+    }
 }
 
 #[derive(Debug, Clone, Display, Error)]
@@ -175,3 +190,35 @@ impl TryRecvError {
         matches!(self, Self::Closed)
     }
 }
+
+#[derive(Debug, Clone, Display, Error)]
+pub enum RecvTimeoutError {
+    /// No matching envelope has been received in time.
+    #[display(fmt = "timed out")]
+    Timeout,
+    /// The mailbox has been closed.
+    #[display(fmt = "mailbox closed")]
+    Closed,
+}
+
+impl RecvTimeoutError {
+    /// Returns whether the error is the `Timeout` variant.
+    #[inline]
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout)
+    }
+
+    /// Returns whether the error is the `Closed` variant.
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Self::Closed)
+    }
+}
+
+/// An error returned by `time::Calendar` constructors.
+#[derive(Debug, Clone, Display, Error)]
+#[display(fmt = "invalid calendar: {reason}")]
+#[non_exhaustive]
+pub struct ParseCalendarError {
+    pub reason: String,
+}
@@ -1,4 +1,4 @@
-use std::{fmt::Debug, future::Future, marker::PhantomData, sync::Arc};
+use std::{fmt::Debug, future::Future, marker::PhantomData, sync::Arc, time::Duration};
 
 use futures::future::BoxFuture;
 
@@ -7,6 +7,7 @@ use crate::{
     context::Context,
     envelope::Envelope,
     exec::{Exec, ExecResult},
+    mailbox::{MailboxParams, MailboxPolicy},
     object::{GroupHandle, GroupVisitor, Object},
     restarting::RestartPolicy,
     routers::Router,
@@ -18,6 +19,7 @@ use crate::{
 pub struct ActorGroup<R, C> {
     restart_policy: RestartPolicy,
     termination_policy: TerminationPolicy,
+    mailbox: MailboxParams,
     stop_order: i8,
     router: R,
     _config: PhantomData<C>,
@@ -29,6 +31,7 @@ impl ActorGroup<(), ()> {
         Self {
             restart_policy: RestartPolicy::default(),
             termination_policy: TerminationPolicy::default(),
+            mailbox: MailboxParams::default(),
             router: (),
             stop_order: 0,
             _config: PhantomData,
@@ -41,6 +44,7 @@ impl<R, C> ActorGroup<R, C> {
         ActorGroup {
             restart_policy: self.restart_policy,
             termination_policy: self.termination_policy,
+            mailbox: self.mailbox,
             router: self.router,
             stop_order: self.stop_order,
             _config: PhantomData,
@@ -63,11 +67,59 @@ impl<R, C> ActorGroup<R, C> {
         self
     }
 
+    /// The maximum number of envelopes in a mailbox of every actor.
+    /// If the limit is reached, `send()` waits and `try_send()` fails.
+    ///
+    /// Can be overridden by the `system.mailbox.capacity` config parameter.
+    /// The new capacity is applied to already running actors on config
+    /// updates, but envelopes over the new limit aren't dropped.
+    ///
+    /// `100_000` by default.
+    ///
+    /// # Panics
+    ///
+    /// If `capacity` is zero, because senders would be blocked forever.
+    #[track_caller]
+    pub fn mailbox_capacity(mut self, capacity: usize) -> Self {
+        assert_ne!(capacity, 0, "mailbox capacity must be non-zero");
+        self.mailbox.capacity = capacity;
+        self
+    }
+
+    /// The behaviour of mailboxes on overflow.
+    ///
+    /// Can be overridden by the `system.mailbox.policy` config parameter
+    /// (one of `Block`, `DropNewest`, `DropOldest` and `Coalesce`).
+    ///
+    /// `MailboxPolicy::block` is used by default.
+    pub fn mailbox_policy(mut self, policy: MailboxPolicy) -> Self {
+        self.mailbox.policy = policy;
+        self
+    }
+
+    /// Closes mailboxes of actors that receive no messages for the specified
+    /// time, like `Terminate::closing()` does. Such actors aren't restarted,
+    /// but started again by later messages with
+    /// `ActorStartCause::Reactivated`. Useful for groups with many
+    /// short-living keyed actors, e.g. per entity.
+    ///
+    /// Keys of passivated actors are kept by the group to detect reactivation
+    /// until the router stops using them, see `Router::is_used()`.
+    ///
+    /// Can be overridden by the `system.mailbox.idle_timeout` config parameter.
+    ///
+    /// Unlimited by default.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.mailbox.idle_timeout = Some(timeout);
+        self
+    }
+
     /// Installs a router.
     pub fn router<R1: Router<C>>(self, router: R1) -> ActorGroup<R1, C> {
         ActorGroup {
             restart_policy: self.restart_policy,
             termination_policy: self.termination_policy,
+            mailbox: self.mailbox,
             router,
             stop_order: self.stop_order,
             _config: self._config,
@@ -110,6 +162,7 @@ impl<R, C> ActorGroup<R, C> {
                 self.router,
                 self.restart_policy,
                 self.termination_policy,
+                self.mailbox,
                 rt_manager,
             ));
 
@@ -22,13 +22,14 @@ use crate::{
     context::Context,
     demux::Demux,
     errors::{RequestError, StartError, StartGroupError},
+    mailbox::MailboxParams,
     message,
     messages::{StartEntrypoint, Terminate, UpdateConfig},
     object::Object,
     scope::{Scope, ScopeGroupShared},
     signal::{Signal, SignalKind},
     subscription::SubscriptionManager,
-    topology::{Topology, SYSTEM_INIT_GROUP_NO},
+    topology::{LocalActorGroup, Topology, SYSTEM_INIT_GROUP_NO},
     tracing::TraceId,
 };
 
@@ -54,7 +55,7 @@ async fn start_entrypoints(ctx: &Context, topology: &Topology, is_check_only: bo
                 Ok(Ok(())) => Ok(()),
                 Ok(Err(e)) => Err(StartError::single(group.name.clone(), e.reason)),
                 Err(RequestError::Ignored) => Ok(()),
-                Err(RequestError::Failed) => Err(StartError::single(
+                Err(RequestError::Failed | RequestError::TimedOut) => Err(StartError::single(
                     group.name.clone(),
                     "config cannot be delivered to the entrypoint".into(),
                 )),
@@ -78,7 +79,7 @@ async fn start_entrypoints(ctx: &Context, topology: &Topology, is_check_only: bo
                     Err(StartError::multiple(group_errors))
                 }
                 Err(RequestError::Ignored) => Ok(()),
-                Err(RequestError::Failed) => Err(StartError::single(
+                Err(RequestError::Failed | RequestError::TimedOut) => Err(StartError::single(
                     group.name,
                     "starting message cannot be delivered to the entrypoint".into(),
                 )),
@@ -165,6 +166,7 @@ pub async fn do_start<F: Future>(
     let group_no = GroupNo::new(SYSTEM_INIT_GROUP_NO, topology.launch_id()).unwrap();
     let entry = topology.book.vacant_entry(group_no);
     let addr = entry.addr();
+    topology.book.set_system_init(addr);
     let ctx = Context::new(topology.book.clone(), Demux::default());
 
     let meta = Arc::new(ActorMeta {
@@ -176,8 +178,10 @@ pub async fn do_start<F: Future>(
     let actor = Actor::new(
         meta.clone(),
         addr,
+        MailboxParams::default(),
         Default::default(),
         Arc::new(SubscriptionManager::new(ctx.clone())),
+        1,
     );
 
     let scope_shared = ScopeGroupShared::new(addr);
@@ -201,8 +205,15 @@ pub async fn do_start<F: Future>(
     scope.within(init).await
 }
 
-#[message]
-struct TerminateSystem;
+/// Starts the termination of the system.
+#[message(priority = high)]
+pub(crate) struct TerminateSystem;
+
+/// Starts the termination of the system because a group has failed.
+/// Unlike `TerminateSystem`, repeats don't terminate the system immediately,
+/// because several groups can fail one after another.
+#[message(priority = high)]
+pub(crate) struct GroupFailed;
 
 #[message]
 struct CheckMemoryUsageTick;
@@ -237,7 +248,7 @@ async fn termination(mut ctx: Context, topology: Topology) {
     let mut oom_prevented = false;
 
     while let Some(envelope) = ctx.recv().await {
-        if envelope.is::<TerminateSystem>() {
+        if envelope.is::<TerminateSystem>() || envelope.is::<GroupFailed>() {
             break;
         }
 
@@ -312,18 +323,22 @@ async fn terminate_groups(ctx: &Context, topology: &Topology, stop_order: i8) {
     let futures = topology
         .locals()
         .filter(|group| group.stop_order == stop_order)
-        .map(|group| async move {
-            let started_at = Instant::now();
-            select! {
-                _ = terminate_group(ctx, group.addr, group.name.clone(), started_at) => {},
-                _ = watch_group(ctx, group.addr, group.name, started_at) => {},
-            }
-        })
+        .map(|group| stop_group(ctx, group))
         .collect::<Vec<_>>();
 
     join_all(futures).await;
 }
 
+/// Terminates the group and waits until it's finished or the termination
+/// is skipped because of timeouts.
+pub(crate) async fn stop_group(ctx: &Context, group: LocalActorGroup) {
+    let started_at = Instant::now();
+    select! {
+        _ = terminate_group(ctx, group.addr, group.name.clone(), started_at) => {},
+        _ = watch_group(ctx, group.addr, group.name, started_at) => {},
+    }
+}
+
 async fn terminate_group(ctx: &Context, addr: Addr, name: String, started_at: Instant) {
     // Terminate::default
 
@@ -18,9 +18,10 @@ pub use crate::{
     envelope::Envelope,
     group::{ActorGroup, Blueprint, TerminationPolicy},
     local::{Local, MoveOwnership},
+    mailbox::MailboxPolicy,
     message::{Message, Request},
     request_table::ResponseToken,
-    restarting::{RestartParams, RestartPolicy},
+    restarting::{RestartParams, RestartPolicy, RetryPolicy, SupervisionStrategy},
     source::{SourceHandle, UnattachedSource},
     topology::Topology,
 };
@@ -45,11 +46,13 @@ pub mod stuck_detection;
 pub mod time;
 pub mod topology;
 pub mod tracing;
+pub mod watch;
 
 mod actor;
 mod addr;
 mod address_book;
 mod context;
+mod dead_letters;
 mod demux;
 mod envelope;
 mod exec;
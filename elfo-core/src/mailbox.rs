@@ -1,88 +1,787 @@
-use futures_intrusive::{
-    buffer::GrowingHeapBuf,
-    channel::{self, GenericChannel},
+use std::{
+    any::TypeId,
+    collections::VecDeque,
+    fmt,
+    hash::{Hash, Hasher},
+    num::NonZeroUsize,
+    sync::Arc,
+    time::Duration,
 };
-use parking_lot::{Mutex, RawMutex};
+
+use fxhash::FxHasher;
+use metrics::increment_counter;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use tokio::sync::Notify;
 
 use crate::{
     envelope::Envelope,
     errors::{SendError, TrySendError},
+    message::Message,
     tracing::TraceId,
 };
 
-// TODO: make mailboxes bounded by time instead of size.
-const LIMIT: usize = 100_000;
+pub(crate) const DEFAULT_CAPACITY: usize = 100_000;
+pub(crate) const DEFAULT_STASH_CAPACITY: usize = 10_000;
+
+// === MailboxPolicy ===
+
+/// The behaviour of a mailbox when it's full.
+///
+/// Every discarded envelope is counted by the `elfo_discarded_messages_total`
+/// metric in the sender's scope. Discarded requests are answered with
+/// `RequestError::Failed`.
+#[derive(Clone)]
+pub struct MailboxPolicy {
+    kind: PolicyKind,
+}
+
+#[derive(Clone)]
+enum PolicyKind {
+    Block,
+    DropNewest,
+    DropOldest,
+    Coalesce(Arc<dyn Coalescer>),
+}
+
+impl Default for MailboxPolicy {
+    fn default() -> Self {
+        Self::block()
+    }
+}
+
+impl fmt::Debug for MailboxPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl MailboxPolicy {
+    /// `send()` waits until there is free space in the mailbox,
+    /// `try_send()` returns `TrySendError::Full`.
+    ///
+    /// This behaviour is used by default.
+    pub fn block() -> Self {
+        Self {
+            kind: PolicyKind::Block,
+        }
+    }
+
+    /// The incoming envelope is discarded.
+    ///
+    /// Both `send()` and `try_send()` never wait and succeed.
+    pub fn drop_newest() -> Self {
+        Self {
+            kind: PolicyKind::DropNewest,
+        }
+    }
+
+    /// The oldest envelope in the mailbox is discarded.
+    ///
+    /// Both `send()` and `try_send()` never wait and succeed.
+    pub fn drop_oldest() -> Self {
+        Self {
+            kind: PolicyKind::DropOldest,
+        }
+    }
+
+    /// An envelope of the same message type is removed from the mailbox,
+    /// and the incoming one is added to the end of the mailbox. Thus, only
+    /// the latest envelope of every message type is kept if the mailbox is
+    /// full. If there is no such envelope, the incoming one is discarded.
+    ///
+    /// Both `send()` and `try_send()` never wait and succeed.
+    pub fn coalesce() -> Self {
+        Self {
+            kind: PolicyKind::Coalesce(Arc::new(ByType)),
+        }
+    }
+
+    /// Like [`MailboxPolicy::coalesce()`], but envelopes are coalesced only
+    /// if they have both the same message type and the same key returned by
+    /// the provided function. Envelopes with the `None` key are never
+    /// coalesced.
+    ///
+    /// # Example
+    /// ```
+    /// # use elfo_core as elfo;
+    /// # use elfo::{message, msg, MailboxPolicy};
+    /// #[message]
+    /// struct OrderBookSnapshot {
+    ///     instrument: u32,
+    /// }
+    ///
+    /// let policy = MailboxPolicy::coalesce_by(|envelope| {
+    ///     msg!(match envelope {
+    ///         snapshot @ OrderBookSnapshot => Some(snapshot.instrument),
+    ///         _ => None,
+    ///     })
+    /// });
+    /// ```
+    pub fn coalesce_by<K>(f: impl Fn(&Envelope) -> Option<K> + Send + Sync + 'static) -> Self
+    where
+        K: Hash + Eq,
+    {
+        Self {
+            kind: PolicyKind::Coalesce(Arc::new(ByKey(f))),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self.kind {
+            PolicyKind::Block => "Block",
+            PolicyKind::DropNewest => "DropNewest",
+            PolicyKind::DropOldest => "DropOldest",
+            PolicyKind::Coalesce(_) => "Coalesce",
+        }
+    }
+}
+
+/// Defines which envelopes can replace each other.
+trait Coalescer: Send + Sync {
+    /// Returns `None` if the envelope cannot be coalesced.
+    fn hash(&self, envelope: &Envelope) -> Option<u64>;
+
+    /// Called only for envelopes with equal hashes.
+    fn is_same(&self, a: &Envelope, b: &Envelope) -> bool;
+}
+
+struct ByType;
+
+impl Coalescer for ByType {
+    fn hash(&self, envelope: &Envelope) -> Option<u64> {
+        Some(hash_of(envelope.message().message_type_id(), ()))
+    }
+
+    fn is_same(&self, a: &Envelope, b: &Envelope) -> bool {
+        a.message().message_type_id() == b.message().message_type_id()
+    }
+}
+
+struct ByKey<F>(F);
+
+impl<F, K> Coalescer for ByKey<F>
+where
+    F: Fn(&Envelope) -> Option<K> + Send + Sync,
+    K: Hash + Eq,
+{
+    fn hash(&self, envelope: &Envelope) -> Option<u64> {
+        let key = (self.0)(envelope)?;
+        Some(hash_of(envelope.message().message_type_id(), key))
+    }
+
+    fn is_same(&self, a: &Envelope, b: &Envelope) -> bool {
+        a.message().message_type_id() == b.message().message_type_id()
+            && matches!(((self.0)(a), (self.0)(b)), (Some(a), Some(b)) if a == b)
+    }
+}
+
+fn hash_of(type_id: TypeId, key: impl Hash) -> u64 {
+    let mut hasher = FxHasher::default();
+    type_id.hash(&mut hasher);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+// === MailboxConfig ===
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct MailboxConfig {
+    /// Overrides the capacity provided by `ActorGroup::mailbox_capacity()`.
+    /// Zero is rejected, because senders would be blocked forever.
+    pub(crate) capacity: Option<NonZeroUsize>,
+    /// Envelopes waiting in the mailbox longer than this are dropped.
+    #[serde(with = "humantime_serde")]
+    pub(crate) max_latency: Option<Duration>,
+    /// Overrides the policy provided by `ActorGroup::mailbox_policy()`.
+    pub(crate) policy: Option<MailboxPolicyConfig>,
+    /// The maximum number of envelopes stashed by `Context::stash()`.
+    pub(crate) stash_capacity: Option<usize>,
+    /// Overrides the timeout provided by `ActorGroup::idle_timeout()`.
+    #[serde(with = "humantime_serde")]
+    pub(crate) idle_timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub(crate) enum MailboxPolicyConfig {
+    Block,
+    DropNewest,
+    DropOldest,
+    Coalesce,
+}
+
+impl MailboxConfig {
+    /// Overrides the blueprint's parameters.
+    pub(crate) fn make_params(&self, default: &MailboxParams) -> MailboxParams {
+        let policy = match (self.policy, &default.policy.kind) {
+            (None, _) => default.policy.clone(),
+            (Some(MailboxPolicyConfig::Block), _) => MailboxPolicy::block(),
+            (Some(MailboxPolicyConfig::DropNewest), _) => MailboxPolicy::drop_newest(),
+            (Some(MailboxPolicyConfig::DropOldest), _) => MailboxPolicy::drop_oldest(),
+            // Keep the blueprint's key function if it's provided.
+            (Some(MailboxPolicyConfig::Coalesce), PolicyKind::Coalesce(_)) => {
+                default.policy.clone()
+            }
+            (Some(MailboxPolicyConfig::Coalesce), _) => MailboxPolicy::coalesce(),
+        };
+
+        MailboxParams {
+            capacity: self.capacity.map_or(default.capacity, NonZeroUsize::get),
+            max_latency: self.max_latency.or(default.max_latency),
+            policy,
+            stash_capacity: self.stash_capacity.unwrap_or(default.stash_capacity),
+            idle_timeout: self.idle_timeout.or(default.idle_timeout),
+        }
+    }
+}
+
+// === MailboxParams ===
+
+#[derive(Debug, Clone)]
+pub(crate) struct MailboxParams {
+    pub(crate) capacity: usize,
+    pub(crate) max_latency: Option<Duration>,
+    pub(crate) policy: MailboxPolicy,
+    pub(crate) stash_capacity: usize,
+    pub(crate) idle_timeout: Option<Duration>,
+}
+
+impl Default for MailboxParams {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_CAPACITY,
+            max_latency: None,
+            policy: MailboxPolicy::default(),
+            stash_capacity: DEFAULT_STASH_CAPACITY,
+            idle_timeout: None,
+        }
+    }
+}
+
+// === Mailbox ===
 
+/// A mailbox consists of two lanes:
+/// * The control lane for messages with high priority, e.g. `Terminate`,
+///   `UpdateConfig` and `Ping`. It's unbounded and always drained first.
+/// * The data lane for other messages. It's bounded by the capacity
+///   and the policy is applied on overflow.
 pub(crate) struct Mailbox {
-    queue: GenericChannel<RawMutex, Envelope, GrowingHeapBuf<Envelope>>,
-    closed_trace_id: Mutex<Option<TraceId>>,
+    inner: Mutex<Inner>,
+    /// Notifies the receiver about new envelopes or closing.
+    rx_notify: Notify,
+    /// Notifies blocked senders about free space or closing.
+    tx_notify: Notify,
+}
+
+struct Inner {
+    control: VecDeque<Envelope>,
+    queue: VecDeque<Slot>,
+    params: MailboxParams,
+    closed_trace_id: Option<TraceId>,
+}
+
+struct Slot {
+    envelope: Envelope,
+    /// Calculated only for the `Coalesce` policy.
+    coalesce_hash: Option<u64>,
+}
+
+enum PushResult {
+    /// The envelope is added, but possibly another one is discarded.
+    Pushed(Option<Envelope>),
+    /// The mailbox is full and the policy is `Block`.
+    Full(Envelope),
+}
+
+impl Inner {
+    fn is_full(&self) -> bool {
+        self.queue.len() >= self.params.capacity
+    }
+
+    fn is_stale(&self, envelope: &Envelope) -> bool {
+        let max_latency = ward!(self.params.max_latency, return false);
+        envelope.created_time().elapsed() > max_latency
+    }
+
+    fn push(&mut self, envelope: Envelope) -> PushResult {
+        if envelope.message().high_priority() {
+            self.control.push_back(envelope);
+            return PushResult::Pushed(None);
+        }
+
+        let coalesce_hash = match &self.params.policy.kind {
+            PolicyKind::Coalesce(coalescer) => coalescer.hash(&envelope),
+            _ => None,
+        };
+
+        let discarded = if self.is_full() {
+            Some(match &self.params.policy.kind {
+                PolicyKind::Block => return PushResult::Full(envelope),
+                PolicyKind::DropNewest => return PushResult::Pushed(Some(envelope)),
+                PolicyKind::DropOldest => match self.queue.pop_front() {
+                    Some(slot) => slot.envelope,
+                    None => return PushResult::Pushed(Some(envelope)),
+                },
+                PolicyKind::Coalesce(coalescer) => {
+                    let hash = ward!(coalesce_hash, return PushResult::Pushed(Some(envelope)));
+                    let index = self.queue.iter().rposition(|slot| {
+                        slot.coalesce_hash == Some(hash)
+                            && coalescer.is_same(&slot.envelope, &envelope)
+                    });
+                    let index = ward!(index, return PushResult::Pushed(Some(envelope)));
+                    self.queue.remove(index).expect("invalid index").envelope
+                }
+            })
+        } else {
+            None
+        };
+
+        self.queue.push_back(Slot {
+            envelope,
+            coalesce_hash,
+        });
+
+        PushResult::Pushed(discarded)
+    }
 }
 
 impl Mailbox {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(params: MailboxParams) -> Self {
         Self {
-            queue: GenericChannel::with_capacity(LIMIT),
-            closed_trace_id: Mutex::new(None),
+            inner: Mutex::new(Inner {
+                control: VecDeque::new(),
+                queue: VecDeque::new(),
+                params,
+                closed_trace_id: None,
+            }),
+            rx_notify: Notify::new(),
+            tx_notify: Notify::new(),
         }
     }
 
-    pub(crate) async fn send(&self, envelope: Envelope) -> Result<(), SendError<Envelope>> {
-        let fut = self.queue.send(envelope);
-        fut.await.map_err(|err| SendError(err.0))
+    /// Changes parameters of the mailbox.
+    ///
+    /// If the new capacity is less than the current number of envelopes,
+    /// nothing is dropped, but new envelopes aren't accepted (or discarded
+    /// according to the policy) until the receiver drains the queue below
+    /// the new capacity.
+    pub(crate) fn configure(&self, params: MailboxParams) {
+        self.inner.lock().params = params;
+
+        // Blocked senders should check the new capacity and policy.
+        self.tx_notify.notify_waiters();
+    }
+
+    pub(crate) fn stash_capacity(&self) -> usize {
+        self.inner.lock().params.stash_capacity
+    }
+
+    pub(crate) fn idle_timeout(&self) -> Option<Duration> {
+        self.inner.lock().params.idle_timeout
+    }
+
+    /// Returns the number of envelopes in the data lane.
+    pub(crate) fn len(&self) -> usize {
+        self.inner.lock().queue.len()
+    }
+
+    pub(crate) async fn send(&self, mut envelope: Envelope) -> Result<(), SendError<Envelope>> {
+        loop {
+            let notified = {
+                let mut inner = self.inner.lock();
+
+                if inner.closed_trace_id.is_some() {
+                    return Err(SendError(envelope));
+                }
+
+                match inner.push(envelope) {
+                    PushResult::Pushed(discarded) => {
+                        let policy = discarded.is_some().then(|| inner.params.policy.as_str());
+                        drop(inner);
+                        self.on_pushed(discarded, policy);
+                        return Ok(());
+                    }
+                    PushResult::Full(returned) => envelope = returned,
+                }
+
+                // NOTE: It is important to create the future under the lock.
+                // `notify_waiters()` wakes up all futures created before the call,
+                // even if they haven't been polled yet, so no wakeup can be lost.
+                self.tx_notify.notified()
+            };
+
+            notified.await;
+        }
     }
 
     pub(crate) fn try_send(&self, envelope: Envelope) -> Result<(), TrySendError<Envelope>> {
-        self.queue.try_send(envelope).map_err(|err| match err {
-            channel::TrySendError::Full(envelope) => TrySendError::Full(envelope),
-            channel::TrySendError::Closed(envelope) => TrySendError::Closed(envelope),
-        })
+        let mut inner = self.inner.lock();
+
+        if inner.closed_trace_id.is_some() {
+            return Err(TrySendError::Closed(envelope));
+        }
+
+        match inner.push(envelope) {
+            PushResult::Pushed(discarded) => {
+                let policy = discarded.is_some().then(|| inner.params.policy.as_str());
+                drop(inner);
+                self.on_pushed(discarded, policy);
+                Ok(())
+            }
+            PushResult::Full(envelope) => Err(TrySendError::Full(envelope)),
+        }
+    }
+
+    fn on_pushed(&self, discarded: Option<Envelope>, policy: Option<&'static str>) {
+        self.rx_notify.notify_one();
+
+        // Drop envelopes outside the lock, because dropping requests can
+        // lead to sending responses.
+        if let (Some(envelope), Some(policy)) = (discarded, policy) {
+            on_discarded(envelope, policy);
+        }
     }
 
     pub(crate) async fn recv(&self) -> RecvResult {
-        let fut = self.queue.receive();
-        match fut.await {
-            Some(envelope) => RecvResult::Data(envelope),
-            None => self.on_close(),
+        loop {
+            if let Some(result) = self.try_recv() {
+                return result;
+            }
+
+            // `notify_one()` stores a permit if there is no waiter,
+            // so a notification between `try_recv()` and this line isn't lost.
+            self.rx_notify.notified().await;
         }
     }
 
     pub(crate) fn try_recv(&self) -> Option<RecvResult> {
-        match self.queue.try_receive() {
-            Ok(envelope) => Some(RecvResult::Data(envelope)),
-            Err(channel::TryReceiveError::Empty) => None,
-            Err(channel::TryReceiveError::Closed) => Some(self.on_close()),
+        let mut inner = self.inner.lock();
+
+        // Envelopes in the control lane are never stale.
+        if let Some(envelope) = inner.control.pop_front() {
+            return Some(RecvResult::Data(envelope));
+        }
+
+        let was_full = inner.is_full();
+
+        match inner.queue.pop_front() {
+            Some(Slot { envelope, .. }) => {
+                let is_full = inner.is_full();
+
+                let is_stale = inner.is_stale(&envelope);
+                drop(inner);
+
+                if was_full && !is_full {
+                    self.tx_notify.notify_waiters();
+                }
+
+                Some(if is_stale {
+                    RecvResult::Stale(envelope)
+                } else {
+                    RecvResult::Data(envelope)
+                })
+            }
+            None => inner.closed_trace_id.map(RecvResult::Closed),
         }
     }
 
     #[cold]
     pub(crate) fn close(&self, trace_id: TraceId) -> bool {
-        // NOTE: It is important that we take the lock here before actually closing the
-        // channel. If we take a lock after closing the channel, data race is
-        // possible when we try to `recv()` after the channel is closed, but
-        // before the `closed_trace_id` is assigned.
-        let mut closed_trace_id = self.closed_trace_id.lock();
-        if self.queue.close().is_newly_closed() {
-            *closed_trace_id = Some(trace_id);
-            true
-        } else {
-            false
+        let mut inner = self.inner.lock();
+        if inner.closed_trace_id.is_some() {
+            return false;
         }
+
+        inner.closed_trace_id = Some(trace_id);
+        drop(inner);
+
+        // `notify_one()` is required to store a permit if the receiver isn't waiting yet.
+        self.rx_notify.notify_waiters();
+        self.rx_notify.notify_one();
+        self.tx_notify.notify_waiters();
+        true
     }
 
     #[cold]
     pub(crate) fn drop_all(&self) {
-        while self.queue.try_receive().is_ok() {}
-    }
+        let mut inner = self.inner.lock();
+        let control = std::mem::take(&mut inner.control);
+        let queue = std::mem::take(&mut inner.queue);
+        drop(inner);
 
-    #[cold]
-    fn on_close(&self) -> RecvResult {
-        let trace_id = self.closed_trace_id.lock().expect("called before close()");
-        RecvResult::Closed(trace_id)
+        // Drop envelopes outside the lock, because dropping requests can
+        // lead to sending responses.
+        drop(control);
+        drop(queue);
+        self.tx_notify.notify_waiters();
     }
 }
 
+#[cold]
+fn on_discarded(envelope: Envelope, policy: &'static str) {
+    increment_counter!("elfo_discarded_messages_total",
+        "message" => envelope.message().name(), "policy" => policy);
+}
+
 #[allow(clippy::large_enum_variant)]
 pub(crate) enum RecvResult {
     Data(Envelope),
+    /// The envelope has been waiting longer than `max_latency`.
+    Stale(Envelope),
     Closed(TraceId),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use elfo_utils::time;
+
+    use super::*;
+    use crate::{envelope::MessageKind, message, Addr};
+
+    #[message]
+    struct Sample(u32);
+
+    #[message]
+    struct Other(u32);
+
+    #[message(priority = high)]
+    struct Control;
+
+    fn params(capacity: usize) -> MailboxParams {
+        MailboxParams {
+            capacity,
+            ..Default::default()
+        }
+    }
+
+    fn with_policy(capacity: usize, policy: MailboxPolicy) -> Mailbox {
+        Mailbox::new(MailboxParams {
+            capacity,
+            policy,
+            ..Default::default()
+        })
+    }
+
+    fn trace_id() -> TraceId {
+        TraceId::try_from(1).unwrap()
+    }
+
+    fn envelope(no: u32) -> Envelope {
+        let kind = MessageKind::Regular { sender: Addr::NULL };
+        Envelope::with_trace_id(Sample(no), kind, trace_id()).upcast()
+    }
+
+    fn unpack(result: Option<RecvResult>) -> Option<u32> {
+        match result? {
+            RecvResult::Data(envelope) => Some(envelope.message().downcast_ref::<Sample>()?.0),
+            RecvResult::Stale(_) | RecvResult::Closed(_) => None,
+        }
+    }
+
+    #[test]
+    fn capacity() {
+        let mailbox = Mailbox::new(params(2));
+        assert!(mailbox.try_send(envelope(1)).is_ok());
+        assert!(mailbox.try_send(envelope(2)).is_ok());
+        assert!(matches!(
+            mailbox.try_send(envelope(3)),
+            Err(TrySendError::Full(_))
+        ));
+
+        // Shrinking doesn't drop envelopes.
+        mailbox.configure(params(1));
+        assert_eq!(unpack(mailbox.try_recv()), Some(1));
+        assert!(mailbox.try_send(envelope(3)).is_err());
+        assert_eq!(unpack(mailbox.try_recv()), Some(2));
+        assert!(mailbox.try_send(envelope(3)).is_ok());
+        assert!(mailbox.try_send(envelope(4)).is_err());
+
+        // Growing allows new envelopes immediately.
+        mailbox.configure(params(3));
+        assert!(mailbox.try_send(envelope(4)).is_ok());
+        assert!(mailbox.try_send(envelope(5)).is_ok());
+        assert!(mailbox.try_send(envelope(6)).is_err());
+    }
+
+    #[test]
+    fn max_latency() {
+        time::with_instant_mock(|mock| {
+            let mailbox = Mailbox::new(MailboxParams {
+                capacity: 10,
+                max_latency: Some(Duration::from_secs(1)),
+                ..Default::default()
+            });
+
+            mailbox.try_send(envelope(1)).unwrap();
+            mock.advance(Duration::from_millis(500));
+            mailbox.try_send(envelope(2)).unwrap();
+            mock.advance(Duration::from_millis(600));
+
+            assert!(matches!(mailbox.try_recv(), Some(RecvResult::Stale(_))));
+            assert_eq!(unpack(mailbox.try_recv()), Some(2));
+        });
+    }
+
+    fn drain(mailbox: &Mailbox) -> Vec<u32> {
+        std::iter::from_fn(|| mailbox.try_recv())
+            .map(|result| match result {
+                RecvResult::Data(envelope) => {
+                    let message = envelope.message();
+                    message
+                        .downcast_ref::<Sample>()
+                        .map(|m| m.0)
+                        .or_else(|| message.downcast_ref::<Other>().map(|m| 100 + m.0))
+                        .unwrap()
+                }
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn drop_newest() {
+        let mailbox = with_policy(2, MailboxPolicy::drop_newest());
+        for no in 1..=4 {
+            assert!(mailbox.try_send(envelope(no)).is_ok());
+        }
+        assert_eq!(drain(&mailbox), vec![1, 2]);
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let mailbox = with_policy(2, MailboxPolicy::drop_oldest());
+        for no in 1..=4 {
+            assert!(mailbox.try_send(envelope(no)).is_ok());
+        }
+        assert_eq!(drain(&mailbox), vec![3, 4]);
+    }
+
+    fn other(no: u32) -> Envelope {
+        let kind = MessageKind::Regular { sender: Addr::NULL };
+        Envelope::with_trace_id(Other(no), kind, trace_id()).upcast()
+    }
+
+    #[test]
+    fn coalesce() {
+        let mailbox = with_policy(2, MailboxPolicy::coalesce());
+        mailbox.try_send(envelope(1)).unwrap();
+        mailbox.try_send(other(1)).unwrap();
+        mailbox.try_send(envelope(2)).unwrap();
+        mailbox.try_send(envelope(3)).unwrap();
+        assert_eq!(drain(&mailbox), vec![101, 3]);
+
+        // Nothing to coalesce with, the incoming envelope is discarded.
+        let mailbox = with_policy(1, MailboxPolicy::coalesce());
+        mailbox.try_send(envelope(1)).unwrap();
+        mailbox.try_send(other(1)).unwrap();
+        assert_eq!(drain(&mailbox), vec![1]);
+    }
+
+    #[test]
+    fn coalesce_by() {
+        let policy = MailboxPolicy::coalesce_by(|envelope| {
+            let sample = envelope.message().downcast_ref::<Sample>()?;
+            (sample.0 > 0).then_some(sample.0 % 2)
+        });
+        let mailbox = with_policy(2, policy);
+        mailbox.try_send(envelope(1)).unwrap();
+        mailbox.try_send(envelope(2)).unwrap();
+        mailbox.try_send(envelope(3)).unwrap();
+        mailbox.try_send(envelope(4)).unwrap();
+        // Neither `0` nor `Other` have a key.
+        mailbox.try_send(envelope(0)).unwrap();
+        mailbox.try_send(other(1)).unwrap();
+        assert_eq!(drain(&mailbox), vec![3, 4]);
+    }
+
+    #[test]
+    fn control_lane() {
+        time::with_instant_mock(|mock| {
+            let mailbox = Mailbox::new(MailboxParams {
+                capacity: 1,
+                max_latency: Some(Duration::from_secs(1)),
+                ..Default::default()
+            });
+            let control = || {
+                let kind = MessageKind::Regular { sender: Addr::NULL };
+                Envelope::with_trace_id(Control, kind, trace_id()).upcast()
+            };
+
+            mailbox.try_send(envelope(1)).unwrap();
+            assert!(mailbox.try_send(envelope(2)).is_err());
+
+            // The control lane ignores the capacity.
+            mailbox.try_send(control()).unwrap();
+            mailbox.try_send(control()).unwrap();
+            mock.advance(Duration::from_secs(2));
+
+            // The control lane is drained first and never stale.
+            for _ in 0..2 {
+                assert!(matches!(
+                    mailbox.try_recv(),
+                    Some(RecvResult::Data(envelope)) if envelope.is::<Control>()
+                ));
+            }
+            assert!(matches!(mailbox.try_recv(), Some(RecvResult::Stale(_))));
+            assert!(mailbox.try_recv().is_none());
+        });
+    }
+
+    #[tokio::test]
+    async fn policy_unblocks_senders() {
+        let mailbox = Arc::new(Mailbox::new(params(1)));
+        mailbox.send(envelope(1)).await.unwrap();
+
+        let mailbox1 = mailbox.clone();
+        let sender = tokio::spawn(async move { mailbox1.send(envelope(2)).await.is_ok() });
+        tokio::task::yield_now().await;
+        assert!(!sender.is_finished());
+
+        mailbox.configure(MailboxParams {
+            capacity: 1,
+            policy: MailboxPolicy::drop_oldest(),
+            ..Default::default()
+        });
+        assert!(sender.await.unwrap());
+        assert_eq!(drain(&mailbox), vec![2]);
+    }
+
+    #[tokio::test]
+    async fn blocked_sender() {
+        let mailbox = Arc::new(Mailbox::new(params(1)));
+        mailbox.send(envelope(1)).await.unwrap();
+
+        let mailbox1 = mailbox.clone();
+        let sender = tokio::spawn(async move { mailbox1.send(envelope(2)).await.is_ok() });
+
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert!(!sender.is_finished());
+
+        assert_eq!(unpack(mailbox.try_recv()), Some(1));
+        assert!(sender.await.unwrap());
+        assert_eq!(unpack(Some(mailbox.recv().await)), Some(2));
+    }
+
+    #[tokio::test]
+    async fn close() {
+        let mailbox = Arc::new(Mailbox::new(params(1)));
+        mailbox.send(envelope(1)).await.unwrap();
+
+        let mailbox1 = mailbox.clone();
+        let sender = tokio::spawn(async move { mailbox1.send(envelope(2)).await.is_err() });
+        tokio::task::yield_now().await;
+
+        assert!(mailbox.close(trace_id()));
+        assert!(!mailbox.close(trace_id()));
+        assert!(sender.await.unwrap());
+
+        // Remaining envelopes are still available.
+        assert_eq!(unpack(Some(mailbox.recv().await)), Some(1));
+        assert!(matches!(mailbox.recv().await, RecvResult::Closed(id) if id == trace_id()));
+    }
+}
@@ -1,4 +1,8 @@
-use std::{any::Any, fmt, ops::Deref};
+use std::{
+    any::{Any, TypeId},
+    fmt,
+    ops::Deref,
+};
 
 use fxhash::{FxHashMap, FxHashSet};
 use linkme::distributed_slice;
@@ -38,6 +42,12 @@ pub trait Message: fmt::Debug + Clone + Any + Send + Serialize + for<'de> Deseri
         self._vtable().dumping_allowed
     }
 
+    #[doc(hidden)]
+    #[inline(always)]
+    fn high_priority(&self) -> bool {
+        self._vtable().high_priority
+    }
+
     #[doc(hidden)]
     #[inline(always)]
     fn upcast(self) -> AnyMessage {
@@ -86,6 +96,12 @@ impl AnyMessage {
         self.data.is::<M>()
     }
 
+    /// Returns the `TypeId` of the underlying message.
+    #[inline]
+    pub(crate) fn message_type_id(&self) -> TypeId {
+        (*self.data).type_id()
+    }
+
     #[inline]
     pub fn downcast_ref<M: Message>(&self) -> Option<&M> {
         self.data.downcast_ref::<M>().map(|message| {
@@ -341,6 +357,8 @@ pub struct MessageVTable {
     pub protocol: &'static str,
     pub labels: &'static [Label],
     pub dumping_allowed: bool, // TODO: introduce `DumpingMode`.
+    /// Messages with high priority are delivered through the control lane.
+    pub high_priority: bool,
     pub clone: fn(&AnyMessage) -> AnyMessage,
     pub debug: fn(&AnyMessage, &mut fmt::Formatter<'_>) -> fmt::Result,
     pub erase: fn(&AnyMessage) -> dumping::ErasedMessage,
@@ -1,11 +1,16 @@
-use std::{fmt::Display, sync::Arc};
+use std::{fmt::Display, sync::Arc, time::Duration};
 
 use derive_more::Constructor;
 
 use crate::{
     actor::{ActorMeta, ActorStatus},
+    addr::Addr,
     config::AnyConfig,
+    errors::RequestError,
+    local::Local,
     message,
+    message::{AnyMessage, Request},
+    request_table::RequestId,
 };
 
 /// A helper type for using in generic code (e.g. as an associated type) to
@@ -19,19 +24,19 @@ pub enum Impossible {}
 
 /// Checks that the actor is able to handle messages.
 /// Routed to all actors in a group by default and handled implicitly by actors.
-#[message(ret = ())]
+#[message(ret = (), priority = high)]
 #[derive(Default)]
 #[non_exhaustive]
 pub struct Ping;
 
-#[message(ret = Result<(), ConfigRejected>)]
+#[message(ret = Result<(), ConfigRejected>, priority = high)]
 #[derive(Constructor)]
 #[non_exhaustive]
 pub struct ValidateConfig {
     pub config: AnyConfig,
 }
 
-#[message(ret = Result<(), ConfigRejected>)]
+#[message(ret = Result<(), ConfigRejected>, priority = high)]
 #[derive(Constructor)]
 #[non_exhaustive]
 pub struct UpdateConfig {
@@ -52,7 +57,7 @@ impl<R: Display> From<R> for ConfigRejected {
     }
 }
 
-#[message(ret = Result<(), StartEntrypointRejected>)]
+#[message(ret = Result<(), StartEntrypointRejected>, priority = high)]
 #[derive(Constructor)]
 #[non_exhaustive]
 pub struct StartEntrypoint {
@@ -74,13 +79,13 @@ pub struct EntrypointError {
     pub reason: String,
 }
 
-#[message]
+#[message(priority = high)]
 #[non_exhaustive]
 pub struct ConfigUpdated {
     // TODO: add `old_config`.
 }
 
-#[message]
+#[message(priority = high)]
 #[derive(Default)]
 #[non_exhaustive]
 pub struct Terminate {
@@ -97,7 +102,7 @@ impl Terminate {
 // === Status ===
 
 // TODO: should it be a request?
-#[message]
+#[message(priority = high)]
 #[derive(Default)]
 #[non_exhaustive]
 pub struct SubscribeToActorStatuses {
@@ -111,9 +116,97 @@ impl SubscribeToActorStatuses {
     }
 }
 
-#[message]
+#[message(priority = high)]
 #[non_exhaustive]
 pub struct ActorStatusReport {
     pub meta: Arc<ActorMeta>,
     pub status: ActorStatus,
 }
+
+/// Delivered to actors monitoring the actor (see `Context::monitor()`) once
+/// it terminates or fails.
+#[message(priority = high)]
+#[non_exhaustive]
+pub struct Down {
+    pub addr: Local<Addr>,
+    pub status: ActorStatus,
+}
+
+// === Failures ===
+
+/// Sent to the escalation group (see `Local::escalation()`) when an actor
+/// fails, i.e. returns an error or panics.
+#[message(priority = high)]
+#[non_exhaustive]
+pub struct ActorFailed {
+    pub meta: Arc<ActorMeta>,
+    /// The error and its sources, from the outermost to the innermost one.
+    /// Contains only the message of a panic.
+    pub error_chain: Vec<String>,
+    /// `None` if the actor won't be restarted.
+    pub restart_in: Option<Duration>,
+}
+
+/// Sent to the escalation group (see `Local::escalation()`) when an actor
+/// isn't restarted anymore because `RestartParams::max_retries()` is reached.
+#[message(priority = high)]
+#[non_exhaustive]
+pub struct RestartsExhausted {
+    pub meta: Arc<ActorMeta>,
+    pub max_retries: u64,
+}
+
+// === Dead letters ===
+
+/// A message that hasn't reached any mailbox, sent to the deadletter group
+/// (see `Local::deadletter()`).
+#[message(priority = high)]
+#[non_exhaustive]
+pub struct DeadLetter {
+    pub original_sender: Local<Addr>,
+    /// The recipient passed to `send_to()` or `try_send_to()`,
+    /// `None` for messages routed by the topology.
+    pub intended_route: Local<Option<Addr>>,
+    pub message: AnyMessage,
+}
+
+// === Requests ===
+
+/// Contains responses to a request sent by `RequestBuilder::id()`.
+/// Delivered to the requester's mailbox once the request is done.
+#[message(priority = high)]
+#[non_exhaustive]
+pub struct RequestResolved {
+    pub request_id: RequestId,
+    pub(crate) responses: Vec<Result<AnyMessage, RequestError>>,
+}
+
+impl RequestResolved {
+    /// Returns the response to a request sent in the `Any` mode.
+    ///
+    /// # Panics
+    ///
+    /// If `R` isn't the type of the sent request.
+    pub fn into_response<R: Request>(self) -> Result<R::Response, RequestError> {
+        let mut responses = self.into_responses::<R>();
+        debug_assert_eq!(responses.len(), 1);
+        responses.pop().expect("missing response")
+    }
+
+    /// Returns all responses to a request sent in the `All`, `First` or
+    /// `Quorum` mode.
+    ///
+    /// # Panics
+    ///
+    /// If `R` isn't the type of the sent request.
+    pub fn into_responses<R: Request>(self) -> Vec<Result<R::Response, RequestError>> {
+        self.responses
+            .into_iter()
+            .map(|response| {
+                let message = response?;
+                let message = message.downcast::<R::Wrapper>().expect("invalid response");
+                Ok(message.into())
+            })
+            .collect()
+    }
+}
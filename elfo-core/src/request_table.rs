@@ -1,13 +1,20 @@
-use std::{fmt, marker::PhantomData, sync::Arc};
+use std::{collections::BTreeSet, fmt, marker::PhantomData, sync::Arc};
 
 use futures_intrusive::sync::ManualResetEvent;
+use metrics::increment_counter;
 use parking_lot::Mutex;
 use slotmap::{new_key_type, Key, SlotMap};
 use smallvec::SmallVec;
+use tokio::{sync::Notify, time::Instant};
 
 use crate::{
-    address_book::AddressBook, envelope::Envelope, errors::RequestError, message::AnyMessage,
-    tracing::TraceId, Addr,
+    address_book::AddressBook,
+    envelope::{Envelope, EnvelopeOwned, MessageKind},
+    errors::RequestError,
+    message::AnyMessage,
+    messages::RequestResolved,
+    tracing::TraceId,
+    Addr,
 };
 
 // === RequestId ===
@@ -42,34 +49,67 @@ pub(crate) struct RequestTable {
     owner: Addr,
     notifier: ManualResetEvent,
     requests: Mutex<SlotMap<RequestId, RequestData>>,
+    /// Deadlines of async requests, processed by the owner in `recv()`.
+    /// Always locked after `requests` if both are needed.
+    deadlines: Mutex<BTreeSet<(Instant, RequestId)>>,
+    /// Notified once an earlier deadline is added.
+    deadline_changed: Notify,
 }
 
 assert_impl_all!(RequestTable: Sync);
 
-type Responses = SmallVec<[Result<Envelope, RequestError>; 1]>;
+pub(crate) type Responses = SmallVec<[Result<Envelope, RequestError>; 1]>;
+
+#[derive(Clone, Copy)]
+pub(crate) enum RequestMode {
+    /// Waits for the first successful response.
+    Any,
+    /// Waits for all responses.
+    All,
+    /// Waits for the specified number of successful responses.
+    First(usize),
+}
 
-#[derive(Default)]
 struct RequestData {
     remainder: usize,
     responses: Responses,
-    collect_all: bool,
+    mode: RequestMode,
+    /// `Some` if responses are delivered to the owner's mailbox
+    /// as `RequestResolved` instead of waking up a waiter.
+    delivery: Option<TraceId>,
+    /// Only async requests have deadlines, others are timed out by waiters.
+    deadline: Option<Instant>,
 }
 
 impl RequestData {
     /// Returns `true` if the request is done.
     fn push(&mut self, response: Result<Envelope, RequestError>) -> bool {
-        // Extra responses (in `any` case).
+        // Extra responses (in `any` and `first` cases).
         if self.remainder == 0 {
             // TODO: move to `ResponseToken` to avoid sending extra responses over network.
-            debug_assert!(!self.collect_all);
+            debug_assert!(!matches!(self.mode, RequestMode::All));
+            on_late_response(&response);
             return false;
         }
 
         self.remainder -= 1;
 
-        if self.collect_all {
-            self.responses.push(response);
-            return self.remainder == 0;
+        match self.mode {
+            RequestMode::Any => {}
+            RequestMode::All => {
+                self.responses.push(response);
+                return self.remainder == 0;
+            }
+            RequestMode::First(n) => {
+                self.responses.push(response);
+
+                // Other responses are discarded.
+                if self.successes() >= n {
+                    self.remainder = 0;
+                }
+
+                return self.remainder == 0;
+            }
         }
 
         // `Any` request contains at most one related response.
@@ -91,6 +131,10 @@ impl RequestData {
 
         self.remainder == 0
     }
+
+    fn successes(&self) -> usize {
+        self.responses.iter().filter(|r| r.is_ok()).count()
+    }
 }
 
 impl RequestTable {
@@ -99,6 +143,8 @@ impl RequestTable {
             owner,
             notifier: ManualResetEvent::new(false),
             requests: Mutex::new(SlotMap::default()),
+            deadlines: Mutex::new(BTreeSet::new()),
+            deadline_changed: Notify::new(),
         }
     }
 
@@ -106,20 +152,164 @@ impl RequestTable {
         &self,
         book: AddressBook,
         trace_id: TraceId,
-        collect_all: bool,
+        mode: RequestMode,
+    ) -> ResponseToken {
+        self.insert(book, trace_id, mode, None, None)
+    }
+
+    /// Creates a request, which responses are delivered to the owner's
+    /// mailbox as `RequestResolved` once the request is done.
+    ///
+    /// If `deadline` is passed, the request is timed out by the owner,
+    /// see `expired()`.
+    pub(crate) fn new_async_request(
+        &self,
+        book: AddressBook,
+        trace_id: TraceId,
+        mode: RequestMode,
+        deadline: Option<Instant>,
+    ) -> ResponseToken {
+        self.insert(book, trace_id, mode, Some(trace_id), deadline)
+    }
+
+    fn insert(
+        &self,
+        book: AddressBook,
+        trace_id: TraceId,
+        mode: RequestMode,
+        delivery: Option<TraceId>,
+        deadline: Option<Instant>,
     ) -> ResponseToken {
         let mut requests = self.requests.lock();
         let request_id = requests.insert(RequestData {
             remainder: 1,
             responses: Responses::new(),
-            collect_all,
+            mode,
+            delivery,
+            deadline,
         });
+
+        if let Some(deadline) = deadline {
+            let mut deadlines = self.deadlines.lock();
+            let is_earliest = match deadlines.first() {
+                Some(&(earliest, _)) => deadline < earliest,
+                None => true,
+            };
+            deadlines.insert((deadline, request_id));
+
+            if is_earliest {
+                self.deadline_changed.notify_one();
+            }
+        }
+
         ResponseToken::new(self.owner, request_id, trace_id, book)
     }
 
+    /// Waits until the earliest deadline of async requests passes.
+    /// Expired requests should be timed out by `time_out_expired()`.
+    pub(crate) async fn expired(&self) {
+        loop {
+            let earliest = self.deadlines.lock().first().map(|&(d, _)| d);
+            let changed = self.deadline_changed.notified();
+
+            match earliest {
+                Some(deadline) => tokio::select! {
+                    _ = tokio::time::sleep_until(deadline) => return,
+                    _ = changed => {}
+                },
+                None => changed.await,
+            }
+        }
+    }
+
+    /// Times out async requests with passed deadlines, their responses
+    /// are delivered to the owner's mailbox.
+    pub(crate) fn time_out_expired(&self, book: &AddressBook) {
+        let now = Instant::now();
+
+        loop {
+            let mut deadlines = self.deadlines.lock();
+            let request_id = match deadlines.first() {
+                Some(&(deadline, request_id)) if deadline <= now => request_id,
+                _ => break,
+            };
+            deadlines.pop_first();
+            drop(deadlines);
+
+            let data = ward!(self.take_timed_out(request_id), continue);
+            deliver(book, self.owner, request_id, data);
+        }
+    }
+
+    /// Creates one more token for the pending request, used to send its copy.
+    /// Returns `None` if the request is already done.
+    pub(crate) fn hedge_request(
+        &self,
+        book: AddressBook,
+        request_id: RequestId,
+        trace_id: TraceId,
+    ) -> Option<ResponseToken> {
+        let mut requests = self.requests.lock();
+        let request = requests.get_mut(request_id)?;
+
+        if request.remainder == 0 {
+            return None;
+        }
+
+        request.remainder += 1;
+        Some(ResponseToken::new(self.owner, request_id, trace_id, book))
+    }
+
     pub(crate) fn cancel_request(&self, request_id: RequestId) {
         let mut requests = self.requests.lock();
-        requests.remove(request_id);
+        let data = ward!(requests.remove(request_id));
+        self.remove_deadline(request_id, &data);
+    }
+
+    fn remove_deadline(&self, request_id: RequestId, data: &RequestData) {
+        let deadline = ward!(data.deadline);
+        self.deadlines.lock().remove(&(deadline, request_id));
+    }
+
+    /// Removes the timed out request. Missing responses are replaced with
+    /// `RequestError::TimedOut`, responses received later are dropped.
+    pub(crate) fn time_out_request(&self, request_id: RequestId) -> Responses {
+        self.take_timed_out(request_id)
+            .expect("unknown request")
+            .responses
+    }
+
+    fn take_timed_out(&self, request_id: RequestId) -> Option<RequestData> {
+        let mut requests = self.requests.lock();
+        let mut data = requests.remove(request_id)?;
+        self.remove_deadline(request_id, &data);
+
+        // The request can be completed right before the timer fires.
+        if data.remainder == 0 && requests.values().all(|data| data.remainder != 0) {
+            self.notifier.reset();
+        }
+
+        drop(requests);
+
+        match data.mode {
+            // Keep the received error, it's more informative.
+            RequestMode::Any => {
+                if data.responses.is_empty() {
+                    data.responses.push(Err(RequestError::TimedOut));
+                }
+            }
+            RequestMode::All => {
+                let missing = (0..data.remainder).map(|_| Err(RequestError::TimedOut));
+                data.responses.extend(missing);
+            }
+            RequestMode::First(n) => {
+                if data.successes() < n {
+                    data.responses.push(Err(RequestError::TimedOut));
+                }
+            }
+        }
+
+        Some(data)
     }
 
     pub(crate) async fn wait(&self, request_id: RequestId) -> Responses {
@@ -163,16 +353,57 @@ impl RequestTable {
         let data = ward!(token.data.take());
         let mut requests = self.requests.lock();
 
-        // `None` here means the request was with `collect_all = false` and
-        // the response has been recieved already.
-        let request = ward!(requests.get_mut(data.request_id));
+        // `None` here means the request was in the `Any` or `First` mode and
+        // enough responses have been recieved already, or the request timed out.
+        let request = ward!(requests.get_mut(data.request_id), else {
+            on_late_response(&response);
+            return;
+        });
+
+        if !request.push(response) {
+            return;
+        }
 
-        if request.push(response) {
+        if request.delivery.is_some() {
+            let request = requests.remove(data.request_id).expect("under lock");
+            self.remove_deadline(data.request_id, &request);
+            drop(requests);
+            deliver(&data.book, self.owner, data.request_id, request);
+        } else {
             self.notifier.set();
         }
     }
 }
 
+fn deliver(book: &AddressBook, owner: Addr, request_id: RequestId, data: RequestData) {
+    let trace_id = data.delivery.expect("not an async request");
+    let message = RequestResolved {
+        request_id,
+        responses: data
+            .responses
+            .into_iter()
+            .map(|response| response.map(|envelope| envelope.unpack_regular()))
+            .collect(),
+    };
+
+    let kind = MessageKind::Regular { sender: owner };
+    let envelope = Envelope::with_trace_id(message, kind, trace_id).upcast();
+
+    // `RequestResolved` uses the control lane, so it fails only if the
+    // owner is closed and doesn't wait for responses anymore.
+    let object = ward!(book.get(owner));
+    let actor = ward!(object.as_actor());
+    let _ = actor.try_send(envelope);
+}
+
+#[cold]
+fn on_late_response(response: &Result<Envelope, RequestError>) {
+    // Errors are produced by dropped tokens, so count only real responses.
+    if response.is_ok() {
+        increment_counter!("elfo_late_responses_total");
+    }
+}
+
 // === ResponseToken ===
 
 #[must_use]
@@ -2,7 +2,7 @@ use std::{num::NonZeroU64, time::Duration};
 
 use serde::Deserialize;
 
-use crate::restarting::restart_policy::{RestartParams, RestartPolicy};
+use crate::restarting::restart_policy::{RestartParams, RestartPolicy, SupervisionStrategy};
 
 #[derive(Debug, Clone, Default, Deserialize)]
 pub(crate) struct RestartPolicyConfig(Option<WhenConfig>);
@@ -25,13 +25,23 @@ struct RestartParamsConfig {
     auto_reset: Option<Duration>,
     max_retries: Option<NonZeroU64>,
     factor: Option<f64>,
+    #[serde(default)]
+    strategy: SupervisionStrategy,
+    intensity: Option<IntensityConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct IntensityConfig {
+    max_restarts: u32,
+    #[serde(with = "humantime_serde")]
+    period: Duration,
 }
 
 impl RestartPolicyConfig {
     pub(crate) fn make_policy(&self) -> Option<RestartPolicy> {
         self.0.as_ref().map(|cfg| match cfg {
-            WhenConfig::Always(rp_cfg) => RestartPolicy::always(rp_cfg.make_params()),
-            WhenConfig::OnFailure(rp_cfg) => RestartPolicy::on_failure(rp_cfg.make_params()),
+            WhenConfig::Always(rp_cfg) => rp_cfg.make_policy(RestartPolicy::always),
+            WhenConfig::OnFailure(rp_cfg) => rp_cfg.make_policy(RestartPolicy::on_failure),
             WhenConfig::Never => RestartPolicy::never(),
         })
     }
@@ -44,4 +54,13 @@ impl RestartParamsConfig {
             .auto_reset(self.auto_reset)
             .max_retries(self.max_retries)
     }
+
+    fn make_policy(&self, mode: fn(RestartParams) -> RestartPolicy) -> RestartPolicy {
+        let policy = mode(self.make_params()).strategy(self.strategy);
+
+        match &self.intensity {
+            Some(cfg) => policy.intensity(cfg.max_restarts, cfg.period),
+            None => policy,
+        }
+    }
 }
@@ -1,6 +1,8 @@
 mod backoff;
 mod config;
 mod restart_policy;
+mod retry_policy;
 
 pub(crate) use self::{backoff::RestartBackoff, config::RestartPolicyConfig};
-pub use restart_policy::{RestartParams, RestartPolicy};
+pub use restart_policy::{RestartParams, RestartPolicy, SupervisionStrategy};
+pub use retry_policy::RetryPolicy;
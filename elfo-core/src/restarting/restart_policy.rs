@@ -1,5 +1,6 @@
 use std::{num::NonZeroU64, time::Duration};
 
+use serde::Deserialize;
 use tracing::warn;
 
 use crate::ActorStatus;
@@ -8,6 +9,8 @@ use crate::ActorStatus;
 #[derive(Debug, Clone, PartialEq)]
 pub struct RestartPolicy {
     pub(crate) mode: RestartMode,
+    pub(crate) strategy: SupervisionStrategy,
+    pub(crate) intensity: Option<RestartIntensity>,
 }
 
 impl Default for RestartPolicy {
@@ -27,18 +30,55 @@ impl RestartPolicy {
     pub fn always(restart_params: RestartParams) -> Self {
         Self {
             mode: RestartMode::Always(restart_params),
+            strategy: SupervisionStrategy::default(),
+            intensity: None,
         }
     }
 
     pub fn on_failure(restart_params: RestartParams) -> Self {
         Self {
             mode: RestartMode::OnFailure(restart_params),
+            strategy: SupervisionStrategy::default(),
+            intensity: None,
         }
     }
 
     pub fn never() -> Self {
         Self {
             mode: RestartMode::Never,
+            strategy: SupervisionStrategy::default(),
+            intensity: None,
+        }
+    }
+
+    /// Sets which actors of the group are restarted together with the
+    /// terminated one, see [SupervisionStrategy].
+    ///
+    /// The strategy is taken from the group's policy (the config or the
+    /// blueprint), policies set by `Context::set_restart_policy()` don't
+    /// affect it.
+    ///
+    /// If the function isn't used, [SupervisionStrategy::OneForOne] is used.
+    pub fn strategy(self, strategy: SupervisionStrategy) -> Self {
+        Self { strategy, ..self }
+    }
+
+    /// Limits the restart intensity of the group. If there are more than
+    /// `max_restarts` restarts within `period`, the group fails: all its
+    /// actors are stopped, new ones aren't spawned, and the system is
+    /// terminated.
+    ///
+    /// Restarts of other actors caused by the [SupervisionStrategy] aren't
+    /// counted. Like the strategy, it's taken from the group's policy only.
+    ///
+    /// If the function isn't used, the intensity is unlimited.
+    pub fn intensity(self, max_restarts: u32, period: Duration) -> Self {
+        Self {
+            intensity: Some(RestartIntensity {
+                max_restarts,
+                period,
+            }),
+            ..self
         }
     }
 
@@ -58,6 +98,30 @@ impl RestartPolicy {
     }
 }
 
+/// Which actors of the group are restarted when one of them is going to be
+/// restarted according to the [RestartPolicy].
+///
+/// Other actors are restarted immediately after they're closed, regardless of
+/// their restart policy. It's intended for groups of strongly coupled actors
+/// that must restart together.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[non_exhaustive]
+pub enum SupervisionStrategy {
+    /// Only the terminated actor is restarted.
+    #[default]
+    OneForOne,
+    /// All actors of the group are restarted.
+    OneForAll,
+    /// The terminated actor and all actors started after it are restarted.
+    RestForOne,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RestartIntensity {
+    pub(crate) max_restarts: u32,
+    pub(crate) period: Duration,
+}
+
 /// Restart parameters for the backoff strategy when an actor restarts based on
 /// the [RestartPolicy].
 #[derive(Debug, Copy, Clone, PartialEq)]
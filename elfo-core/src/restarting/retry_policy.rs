@@ -0,0 +1,47 @@
+use std::{num::NonZeroU64, time::Duration};
+
+use crate::RestartParams;
+
+/// The policy of retrying failed and timed out requests, see [`RequestBuilder::retry()`].
+///
+/// Attempts are delayed using the same backoff strategy as restarts of
+/// actors, see [`RestartParams`].
+///
+/// [`RequestBuilder::retry()`]: crate::RequestBuilder::retry
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub(crate) params: RestartParams,
+}
+
+impl RetryPolicy {
+    /// Creates a new instance with the specified minimum and maximum backoff
+    /// durations. The default values for `max_retries` and `factor` are set
+    /// as follows:
+    /// - `max_retries = 3`
+    /// - `factor = 2.0`
+    pub fn new(min_backoff: Duration, max_backoff: Duration) -> Self {
+        let params = RestartParams::new(min_backoff, max_backoff)
+            // Retries of the same request are never considered successful.
+            .auto_reset(Duration::MAX)
+            .max_retries(NonZeroU64::new(3));
+
+        Self { params }
+    }
+
+    /// Sets the factor used to calculate the next backoff duration.
+    /// See [`RestartParams::factor()`] for details.
+    pub fn factor(self, factor: impl Into<Option<f64>>) -> Self {
+        Self {
+            params: self.params.factor(factor),
+        }
+    }
+
+    /// Sets the maximum number of retries, not including the first attempt.
+    ///
+    /// `None` does not change the `max_retries` setting.
+    pub fn max_retries(self, max_retries: impl Into<Option<NonZeroU64>>) -> Self {
+        Self {
+            params: self.params.max_retries(max_retries),
+        }
+    }
+}
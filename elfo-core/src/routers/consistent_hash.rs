@@ -0,0 +1,181 @@
+use std::{
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use arc_swap::ArcSwap;
+use fxhash::FxHasher64;
+
+use super::{Outcome, Router};
+use crate::envelope::Envelope;
+
+/// The number of points on the ring per shard.
+const POINTS_PER_SHARD: usize = 128;
+const POINT_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Spreads messages over shards, which number is taken from the config.
+///
+/// Messages are mapped to shard keys (`0..shards`) on a hash ring, so when
+/// the number of shards is changed, only a minimal set of hashes moves to
+/// other shards. Actors of shards that are no longer used get `Terminate`.
+///
+/// The key-extraction closure returns `None` for messages that should be
+/// routed by default, see [`Outcome::Default`].
+///
+/// # Example
+/// ```ignore
+/// ConsistentHashRouter::new(
+///     |config: &Config| config.shards,
+///     |envelope| {
+///         msg!(match envelope {
+///             OrderPlaced { account_id, .. } => Some(*account_id),
+///             _ => None,
+///         })
+///     },
+/// )
+/// ```
+pub struct ConsistentHashRouter<C, S, E> {
+    config: PhantomData<C>,
+    shards: S,
+    extract: E,
+    ring: ArcSwap<Ring>,
+}
+
+impl<C, S, E, T> ConsistentHashRouter<C, S, E>
+where
+    C: Send + Sync + 'static,
+    S: Fn(&C) -> usize + Send + Sync + 'static,
+    E: Fn(&Envelope) -> Option<T> + Send + Sync + 'static,
+    T: Hash,
+{
+    #[inline]
+    pub fn new(shards: S, extract: E) -> Self {
+        Self {
+            config: PhantomData,
+            shards,
+            extract,
+            ring: ArcSwap::default(),
+        }
+    }
+}
+
+impl<C, S, E, T> Router<C> for ConsistentHashRouter<C, S, E>
+where
+    C: Send + Sync + 'static,
+    S: Fn(&C) -> usize + Send + Sync + 'static,
+    E: Fn(&Envelope) -> Option<T> + Send + Sync + 'static,
+    T: Hash,
+{
+    type Key = usize;
+
+    fn update(&self, config: &C) {
+        let shards = (self.shards)(config);
+
+        if self.ring.load().shards != shards {
+            self.ring.store(Arc::new(Ring::new(shards)));
+        }
+    }
+
+    #[inline]
+    fn route(&self, envelope: &Envelope) -> Outcome<Self::Key> {
+        let key = ward!((self.extract)(envelope), return Outcome::Default);
+
+        match self.ring.load().get(hash(&key)) {
+            Some(shard) => Outcome::Unicast(shard),
+            None => Outcome::Discard,
+        }
+    }
+
+    #[inline]
+    fn is_used(&self, key: &Self::Key) -> bool {
+        *key < self.ring.load().shards
+    }
+}
+
+#[derive(Default)]
+struct Ring {
+    shards: usize,
+    /// Sorted by hash.
+    points: Vec<(u64, usize)>,
+}
+
+impl Ring {
+    fn new(shards: usize) -> Self {
+        let mut points = (0..shards)
+            .flat_map(|shard| (0..POINTS_PER_SHARD).map(move |point| (shard, point)))
+            // Seeded to avoid collisions with hashes of small integer keys.
+            .map(|(shard, point)| (hash(&(POINT_SEED, shard as u64, point as u64)), shard))
+            .collect::<Vec<_>>();
+
+        points.sort_unstable();
+        Self { shards, points }
+    }
+
+    fn get(&self, hash: u64) -> Option<usize> {
+        if self.points.is_empty() {
+            return None;
+        }
+
+        // The first point clockwise, wrapping around the ring.
+        let index = self.points.partition_point(|(point, _)| *point < hash);
+        Some(self.points[index % self.points.len()].1)
+    }
+}
+
+/// Must be stable across versions and nodes, so `DefaultHasher` isn't used.
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = FxHasher64::default();
+    value.hash(&mut hasher);
+
+    // `FxHasher` is weak, so finalize it like `splitmix64` does.
+    let mut hash = hasher.finish();
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assign(ring: &Ring) -> Vec<usize> {
+        (0..10_000u64)
+            .map(|key| ring.get(hash(&key)).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(Ring::new(0).get(hash(&42)), None);
+    }
+
+    #[test]
+    fn balance() {
+        let shards = assign(&Ring::new(8));
+
+        for shard in 0..8 {
+            let count = shards.iter().filter(|s| **s == shard).count();
+            assert!((800..1700).contains(&count), "{shard}: {count}");
+        }
+    }
+
+    #[test]
+    fn minimal_movement() {
+        let before = assign(&Ring::new(8));
+
+        // Only hashes of the new shard are moved.
+        let after = assign(&Ring::new(9));
+        for (before, after) in before.iter().zip(&after) {
+            assert!(before == after || *after == 8);
+        }
+        let moved = after.iter().filter(|s| **s == 8).count();
+        assert!((600..1700).contains(&moved), "{moved}");
+
+        // Only hashes of the removed shard are moved.
+        let after = assign(&Ring::new(7));
+        for (before, after) in before.iter().zip(&after) {
+            assert!(before == after || *before == 7);
+        }
+    }
+}
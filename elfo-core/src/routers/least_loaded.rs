@@ -0,0 +1,96 @@
+use std::marker::PhantomData;
+
+use super::{round_robin::Pool, Outcome, RouteContext, Router};
+use crate::envelope::Envelope;
+
+/// Routes messages to the actor with the fewest envelopes in its mailbox
+/// among a pool of actors with keys `0..size`. Missing actors are considered
+/// idle and started. Ties are broken in turn.
+///
+/// The size of the pool is taken from the config, actors that are no longer
+/// in the pool get `Terminate` on config updates.
+///
+/// The filter selects messages for the pool, other messages are routed by
+/// default, see [`Outcome::Default`].
+///
+/// Mailboxes are checked only when the router is called by the supervisor.
+/// Without the group's state, e.g. when `Router::route()` is called directly,
+/// it works like [`RoundRobinRouter`](super::RoundRobinRouter).
+pub struct LeastLoadedRouter<C, S, F> {
+    config: PhantomData<C>,
+    size: S,
+    filter: F,
+    pool: Pool,
+}
+
+impl<C, S, F> LeastLoadedRouter<C, S, F>
+where
+    C: Send + Sync + 'static,
+    S: Fn(&C) -> usize + Send + Sync + 'static,
+    F: Fn(&Envelope) -> bool + Send + Sync + 'static,
+{
+    #[inline]
+    pub fn new(size: S, filter: F) -> Self {
+        Self {
+            config: PhantomData,
+            size,
+            filter,
+            pool: Pool::default(),
+        }
+    }
+}
+
+impl<C, S, F> Router<C> for LeastLoadedRouter<C, S, F>
+where
+    C: Send + Sync + 'static,
+    S: Fn(&C) -> usize + Send + Sync + 'static,
+    F: Fn(&Envelope) -> bool + Send + Sync + 'static,
+{
+    type Key = usize;
+
+    #[inline]
+    fn update(&self, config: &C) {
+        self.pool.resize((self.size)(config));
+    }
+
+    #[inline]
+    fn route(&self, envelope: &Envelope) -> Outcome<Self::Key> {
+        if !(self.filter)(envelope) {
+            return Outcome::Default;
+        }
+
+        match self.pool.next() {
+            Some(key) => Outcome::Unicast(key),
+            None => Outcome::Discard,
+        }
+    }
+
+    fn route_with_context(
+        &self,
+        envelope: &Envelope,
+        ctx: &RouteContext<'_, Self::Key>,
+    ) -> Outcome<Self::Key> {
+        if !(self.filter)(envelope) {
+            return Outcome::Default;
+        }
+
+        let size = self.pool.size();
+        if size == 0 {
+            return Outcome::Discard;
+        }
+
+        let start = self.pool.advance() % size;
+
+        let key = (start..size)
+            .chain(0..start)
+            .min_by_key(|key| ctx.mailbox_len(key).unwrap_or(0))
+            .expect("the pool is not empty");
+
+        Outcome::Unicast(key)
+    }
+
+    #[inline]
+    fn is_used(&self, key: &Self::Key) -> bool {
+        self.pool.contains(*key)
+    }
+}
@@ -3,17 +3,109 @@ use std::{
     hash::Hash,
 };
 
-use crate::{envelope::Envelope, msg};
+use dashmap::DashMap;
+use fxhash::FxBuildHasher;
 
-pub use self::map::MapRouter;
+use crate::{actor::ActorStatusKind, envelope::Envelope, msg, object::ObjectArc};
 
+pub use self::{
+    consistent_hash::ConsistentHashRouter, least_loaded::LeastLoadedRouter, map::MapRouter,
+    round_robin::RoundRobinRouter,
+};
+
+mod consistent_hash;
+mod least_loaded;
 mod map;
+mod round_robin;
 
 pub trait Router<C>: Send + Sync + 'static {
     type Key: Clone + Hash + Eq + Display + Send + Sync; // TODO: why is `Sync` required?
 
     fn update(&self, _config: &C) {}
     fn route(&self, envelope: &Envelope) -> Outcome<Self::Key>;
+
+    /// Routes the envelope with access to the group's state.
+    /// The supervisor calls this method, by default it calls `route()`.
+    #[inline]
+    fn route_with_context(
+        &self,
+        envelope: &Envelope,
+        _ctx: &RouteContext<'_, Self::Key>,
+    ) -> Outcome<Self::Key> {
+        self.route(envelope)
+    }
+
+    /// Returns `false` if the router doesn't use the key anymore, usually
+    /// after `update()`. Such actors get `Terminate` on config updates and
+    /// aren't restarted.
+    #[inline]
+    fn is_used(&self, _key: &Self::Key) -> bool {
+        true
+    }
+}
+
+/// The state of the group available to routers.
+///
+/// Actors are considered live until they are removed from the group,
+/// i.e. restarting actors are live, but terminated and failed ones without
+/// further restarts aren't.
+///
+/// # Example
+/// Routes to any live actor, otherwise spawns the actor with the key `0`:
+/// ```ignore
+/// fn route_with_context(&self, envelope: &Envelope, ctx: &RouteContext<'_, u32>) -> Outcome<u32> {
+///     let key = ctx
+///         .keys()
+///         .find(|key| ctx.status(key) == Some(ActorStatusKind::Normal))
+///         .unwrap_or(0);
+///     Outcome::Unicast(key)
+/// }
+/// ```
+pub struct RouteContext<'a, K> {
+    objects: &'a DashMap<K, ObjectArc, FxBuildHasher>,
+}
+
+impl<'a, K: Clone + Hash + Eq> RouteContext<'a, K> {
+    pub(crate) fn new(objects: &'a DashMap<K, ObjectArc, FxBuildHasher>) -> Self {
+        Self { objects }
+    }
+
+    /// Returns keys of live actors in arbitrary order.
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.objects.iter().map(|item| item.key().clone())
+    }
+
+    /// Returns `true` if there is a live actor with the specified key.
+    pub fn contains(&self, key: &K) -> bool {
+        self.objects.contains_key(key)
+    }
+
+    /// Returns the status of the actor with the specified key,
+    /// or `None` if there is no such actor.
+    pub fn status(&self, key: &K) -> Option<ActorStatusKind> {
+        let object = self.objects.get(key)?;
+        let actor = object.as_actor()?;
+        Some(actor.status_kind())
+    }
+
+    /// Returns how many times the actor with the specified key has been
+    /// started, including restarts, or `None` if there is no such actor.
+    pub fn spawn_count(&self, key: &K) -> Option<u32> {
+        let object = self.objects.get(key)?;
+        let actor = object.as_actor()?;
+        Some(actor.spawn_count())
+    }
+
+    /// Returns the number of envelopes waiting in the mailbox of the actor
+    /// with the specified key, or `None` if there is no such actor.
+    ///
+    /// Messages from the control lane, e.g. `Terminate` and `UpdateConfig`,
+    /// aren't counted.
+    pub fn mailbox_len(&self, key: &K) -> Option<usize> {
+        let object = self.objects.get(key)?;
+        let actor = object.as_actor()?;
+        Some(actor.mailbox_len())
+    }
 }
 
 /// Specifies which actors will get a message.
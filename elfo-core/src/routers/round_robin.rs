@@ -0,0 +1,110 @@
+use std::{
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{Outcome, Router};
+use crate::envelope::Envelope;
+
+/// Spreads messages over a pool of actors with keys `0..size` in turn.
+/// The size of the pool is taken from the config, actors that are no longer
+/// in the pool get `Terminate` on config updates.
+///
+/// The filter selects messages for the pool, other messages are routed by
+/// default, see [`Outcome::Default`].
+///
+/// # Example
+/// ```ignore
+/// RoundRobinRouter::new(
+///     |config: &Config| config.workers,
+///     |envelope| msg!(match envelope {
+///         Job => true,
+///         _ => false,
+///     }),
+/// )
+/// ```
+pub struct RoundRobinRouter<C, S, F> {
+    config: PhantomData<C>,
+    size: S,
+    filter: F,
+    pool: Pool,
+}
+
+impl<C, S, F> RoundRobinRouter<C, S, F>
+where
+    C: Send + Sync + 'static,
+    S: Fn(&C) -> usize + Send + Sync + 'static,
+    F: Fn(&Envelope) -> bool + Send + Sync + 'static,
+{
+    #[inline]
+    pub fn new(size: S, filter: F) -> Self {
+        Self {
+            config: PhantomData,
+            size,
+            filter,
+            pool: Pool::default(),
+        }
+    }
+}
+
+impl<C, S, F> Router<C> for RoundRobinRouter<C, S, F>
+where
+    C: Send + Sync + 'static,
+    S: Fn(&C) -> usize + Send + Sync + 'static,
+    F: Fn(&Envelope) -> bool + Send + Sync + 'static,
+{
+    type Key = usize;
+
+    #[inline]
+    fn update(&self, config: &C) {
+        self.pool.resize((self.size)(config));
+    }
+
+    #[inline]
+    fn route(&self, envelope: &Envelope) -> Outcome<Self::Key> {
+        if !(self.filter)(envelope) {
+            return Outcome::Default;
+        }
+
+        match self.pool.next() {
+            Some(key) => Outcome::Unicast(key),
+            None => Outcome::Discard,
+        }
+    }
+
+    #[inline]
+    fn is_used(&self, key: &Self::Key) -> bool {
+        self.pool.contains(*key)
+    }
+}
+
+/// Keys `0..size` with a cursor, shared by pool routers.
+#[derive(Default)]
+pub(super) struct Pool {
+    size: AtomicUsize,
+    cursor: AtomicUsize,
+}
+
+impl Pool {
+    pub(super) fn resize(&self, size: usize) {
+        self.size.store(size, Ordering::Relaxed);
+    }
+
+    pub(super) fn size(&self) -> usize {
+        self.size.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn contains(&self, key: usize) -> bool {
+        key < self.size()
+    }
+
+    /// Returns the next key in turn, or `None` if the pool is empty.
+    pub(super) fn next(&self) -> Option<usize> {
+        let size = self.size();
+        (size > 0).then(|| self.advance() % size)
+    }
+
+    pub(super) fn advance(&self) -> usize {
+        self.cursor.fetch_add(1, Ordering::Relaxed)
+    }
+}
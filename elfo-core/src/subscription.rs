@@ -16,6 +16,10 @@ impl SubscriptionManager {
         }
     }
 
+    pub(crate) fn context(&self) -> &Context {
+        &self.ctx
+    }
+
     pub(crate) fn add(&self, addr: Addr) -> bool {
         let mut subscribers = self.subscribers.write();
 
@@ -1,15 +1,25 @@
 use std::{
-    any::Any, future::Future, mem, ops::Deref, panic::AssertUnwindSafe, sync::Arc, time::Duration,
+    any::Any,
+    collections::VecDeque,
+    future::Future,
+    mem,
+    ops::Deref,
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
-use dashmap::DashMap;
+use dashmap::{DashMap, DashSet};
 use futures::{future::BoxFuture, FutureExt};
 use fxhash::FxBuildHasher;
 use metrics::{decrement_gauge, increment_gauge};
 use parking_lot::RwLock;
 use tracing::{debug, error, error_span, info, warn, Instrument, Span};
 
-use elfo_utils::CachePadded;
+use elfo_utils::{time::Instant, CachePadded};
 
 use self::{error_chain::ErrorChain, measure_poll::MeasurePoll};
 use crate::{
@@ -19,11 +29,13 @@ use crate::{
     envelope::Envelope,
     exec::{Exec, ExecResult},
     group::TerminationPolicy,
-    message::Request,
+    init::GroupFailed,
+    mailbox::MailboxParams,
+    message::{Message, Request},
     messages, msg,
     object::{GroupVisitor, Object, ObjectArc},
-    restarting::{RestartBackoff, RestartPolicy},
-    routers::{Outcome, Router},
+    restarting::{RestartBackoff, RestartPolicy, SupervisionStrategy},
+    routers::{Outcome, RouteContext, Router},
     runtime::RuntimeManager,
     scope::{self, Scope, ScopeGroupShared},
     subscription::SubscriptionManager,
@@ -38,9 +50,15 @@ pub(crate) struct Supervisor<R: Router<C>, C, X> {
     meta: Arc<ActorMeta>,
     restart_policy: RestartPolicy,
     termination_policy: TerminationPolicy,
+    mailbox: MailboxParams,
     span: Span,
     context: Context,
     objects: DashMap<R::Key, ObjectArc, FxBuildHasher>,
+    /// Keys of passivated actors, see `ActorGroup::idle_timeout()`.
+    passivated: DashSet<R::Key, FxBuildHasher>,
+    /// Numbers of the first starts, see `SupervisionStrategy::RestForOne`.
+    start_order: DashMap<R::Key, u64, FxBuildHasher>,
+    next_start_no: AtomicU64,
     router: R,
     exec: X,
     control: CachePadded<RwLock<ControlBlock<C>>>,
@@ -54,6 +72,8 @@ struct ControlBlock<C> {
     user_config: Option<Arc<C>>,
     is_started: bool,
     stop_spawning: bool,
+    /// Times of recent restarts, see `RestartPolicy::intensity()`.
+    restarts: VecDeque<Instant>,
 }
 
 /// Returns `None` if cannot be spawned.
@@ -65,7 +85,11 @@ macro_rules! get_or_spawn {
             None => $this
                 .objects
                 .entry(key.clone())
-                .or_try_insert_with(|| $this.spawn(key, $start_info, Default::default()).ok_or(()))
+                .or_try_insert_with(|| {
+                    $this
+                        .spawn(key, $start_info, 1, Default::default())
+                        .ok_or(())
+                })
                 .map(|o| o.downgrade()) // FIXME: take an exclusive lock here.
                 .ok(),
         }
@@ -79,6 +103,7 @@ where
     <X::Output as Future>::Output: ExecResult,
     C: Config,
 {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         ctx: Context,
         group: String,
@@ -86,6 +111,7 @@ where
         router: R,
         restart_policy: RestartPolicy,
         termination_policy: TerminationPolicy,
+        mailbox: MailboxParams,
         rt_manager: RuntimeManager,
     ) -> Self {
         let control = ControlBlock {
@@ -93,6 +119,7 @@ where
             user_config: None,
             is_started: false,
             stop_spawning: false,
+            restarts: VecDeque::new(),
         };
 
         let status_subscription = SubscriptionManager::new(ctx.clone());
@@ -105,7 +132,11 @@ where
             }),
             restart_policy,
             termination_policy,
+            mailbox,
             objects: DashMap::default(),
+            passivated: DashSet::default(),
+            start_order: DashMap::default(),
+            next_start_no: AtomicU64::new(0),
             router,
             exec,
             control: CachePadded(RwLock::new(control)),
@@ -146,7 +177,7 @@ where
                     } else {
                         drop(control);
                         envelope.set_message(messages::ValidateConfig { config });
-                        self.router.route(&envelope).or(Outcome::Discard)
+                        self.route(&envelope).or(Outcome::Discard)
                     }
                 }
                 Err(reason) => {
@@ -170,7 +201,12 @@ where
                     control.is_started = true;
                     drop(control);
 
-                    let outcome = self.router.route(&envelope);
+                    if !only_spawn {
+                        self.update_mailboxes();
+                        self.terminate_unused();
+                    }
+
+                    let outcome = self.route(&envelope);
 
                     if only_spawn {
                         self.spawn_on_group_mounted(outcome);
@@ -206,13 +242,13 @@ where
                     }
                 }
 
-                self.router.route(&envelope).or(Outcome::Broadcast)
+                self.route(&envelope).or(Outcome::Broadcast)
             }
             messages::Ping => {
-                self.router.route(&envelope).or(Outcome::Broadcast)
+                self.route(&envelope).or(Outcome::Broadcast)
             }
             _ => {
-                self.router.route(&envelope).or(Outcome::Discard)
+                self.route(&envelope).or(Outcome::Discard)
             }
         });
 
@@ -245,6 +281,11 @@ where
         }
     }
 
+    fn route(&self, envelope: &Envelope) -> Outcome<R::Key> {
+        let ctx = RouteContext::new(&self.objects);
+        self.router.route_with_context(envelope, &ctx)
+    }
+
     fn visit_multiple(
         &self,
         envelope: Envelope,
@@ -271,6 +312,7 @@ where
         self: &Arc<Self>,
         key: R::Key,
         start_info: ActorStartInfo,
+        spawn_count: u32,
         mut backoff: RestartBackoff,
     ) -> Option<ObjectArc> {
         let control = self.control.read();
@@ -278,6 +320,18 @@ where
             return None;
         }
 
+        // Passivated actors are started again by messages.
+        let start_info = if self.passivated.remove(&key).is_some() {
+            ActorStartInfo::on_reactivation()
+        } else {
+            start_info
+        };
+
+        // Restarted actors keep their place in the order.
+        self.start_order
+            .entry(key.clone())
+            .or_insert_with(|| self.next_start_no.fetch_add(1, Ordering::Relaxed));
+
         let group_no = self.context.group().group_no().expect("invalid group addr");
         let entry = self.context.book().vacant_entry(group_no);
         let addr = entry.addr();
@@ -291,6 +345,7 @@ where
         );
 
         let system_config = control.system_config.clone();
+        let mailbox_params = self.mailbox_params(&system_config);
 
         let user_config = control
             .user_config
@@ -306,7 +361,14 @@ where
 
         drop(control);
 
+        let meta = Arc::new(ActorMeta {
+            group: self.meta.group.clone(),
+            key: key_str,
+        });
+
         let sv = self.clone();
+        let actor_meta = meta.clone();
+        let key1 = key.clone();
 
         // TODO: move to `harness.rs`.
         let fut = async move {
@@ -314,8 +376,11 @@ where
 
             info!(%addr, thread = %thread.name().unwrap_or("?"), "started");
 
-            sv.objects
-                .get(&key)
+            // The key can belong to another actor after passivation,
+            // so the actor is found by its address.
+            sv.context
+                .book()
+                .get(addr)
                 .expect("where is the current actor?")
                 .as_actor()
                 .expect("a supervisor stores only actors")
@@ -324,14 +389,42 @@ where
             // It must be called after `entry.insert()`.
             let ctx = ctx.with_addr(addr).with_start_info(start_info);
             let fut = AssertUnwindSafe(async { sv.exec.exec(ctx).await.unify() }).catch_unwind();
-            let new_status = match fut.await {
-                Ok(Ok(())) => ActorStatus::TERMINATED,
-                Ok(Err(err)) => ActorStatus::FAILED.with_details(ErrorChain(&*err)),
-                Err(panic) => ActorStatus::FAILED.with_details(panic_to_string(panic)),
+            let (mut new_status, mut error_chain) = match fut.await {
+                Ok(Ok(())) => (ActorStatus::TERMINATED, None),
+                Ok(Err(err)) => {
+                    let chain = ErrorChain(&*err);
+                    (
+                        ActorStatus::FAILED.with_details(&chain),
+                        Some(chain.to_vec()),
+                    )
+                }
+                Err(panic) => {
+                    let panic = panic_to_string(panic);
+                    (ActorStatus::FAILED.with_details(&panic), Some(vec![panic]))
+                }
             };
 
-            let restart_after = {
-                let object = sv.objects.get(&key).expect("where is the current actor?");
+            // Linked actors fail together, see `Context::link()`.
+            let linked_failure = sv
+                .context
+                .book()
+                .get(addr)
+                .expect("where is the current actor?")
+                .as_actor()
+                .expect("a supervisor stores only actors")
+                .take_linked_failure();
+
+            if let Some(details) = linked_failure.filter(|_| !new_status.is_failed()) {
+                new_status = ActorStatus::FAILED.with_details(&details);
+                error_chain = Some(vec![details]);
+            }
+
+            let (restart_after, exhausted, is_requested, is_passivated, group_restart_policy) = {
+                let object = sv
+                    .context
+                    .book()
+                    .get(addr)
+                    .expect("where is the current actor?");
 
                 let actor = object.as_actor().expect("a supervisor stores only actors");
 
@@ -344,23 +437,68 @@ where
                     .restart_policy
                     .make_policy()
                     .unwrap_or(sv.restart_policy.clone());
-                let restart_policy = actor.restart_policy().unwrap_or(default_restart_policy);
-
-                let restarting_allowed = restart_policy.restarting_allowed(&new_status)
-                    && !sv.control.read().stop_spawning;
+                let restart_policy = actor
+                    .restart_policy()
+                    .unwrap_or_else(|| default_restart_policy.clone());
+
+                let is_passivated = actor.is_passivated();
+                let is_requested = actor.is_restart_requested();
+                let restarting_allowed = (is_requested
+                    || restart_policy.restarting_allowed(&new_status))
+                    && !sv.control.read().stop_spawning
+                    && sv.router.is_used(&key)
+                    && !is_passivated;
 
                 actor.set_status(new_status);
 
-                restarting_allowed
-                    .then(|| {
-                        restart_policy
-                            .restart_params()
-                            .and_then(|p| backoff.next(&p))
-                    })
-                    .flatten()
+                let mut exhausted = None;
+                let restart_after = if !restarting_allowed {
+                    None
+                } else if is_requested {
+                    // Restarted by the group's strategy.
+                    Some(Duration::ZERO)
+                } else if let Some(params) = restart_policy.restart_params() {
+                    let after = backoff.next(&params);
+                    exhausted = after.is_none().then_some(params.max_retries.get());
+                    after
+                } else {
+                    None
+                };
+
+                (
+                    restart_after,
+                    exhausted,
+                    is_requested,
+                    is_passivated,
+                    default_restart_policy,
+                )
+            };
+
+            // Restarts caused by the group's strategy don't affect other actors.
+            let restart_after = match restart_after {
+                Some(after) if !is_requested => {
+                    sv.on_restart(&key, &group_restart_policy).then_some(after)
+                }
+                restart_after => restart_after,
             };
 
-            if let Some(after) = restart_after {
+            if let Some(error_chain) = error_chain {
+                sv.escalate(messages::ActorFailed {
+                    meta: actor_meta.clone(),
+                    error_chain,
+                    restart_in: restart_after,
+                });
+            }
+
+            if let Some(max_retries) = exhausted {
+                warn!(max_retries, "restarts are exhausted");
+                sv.escalate(messages::RestartsExhausted {
+                    meta: actor_meta,
+                    max_retries,
+                });
+            }
+
+            let removed = if let Some(after) = restart_after {
                 if after == Duration::ZERO {
                     debug!("actor will be restarted immediately");
                 } else {
@@ -375,34 +513,42 @@ where
                 scope::set_trace_id(TraceId::generate());
 
                 backoff.start();
-                if let Some(object) = sv.spawn(key.clone(), ActorStartInfo::on_restart(), backoff) {
+                let start_info = ActorStartInfo::on_restart();
+                if let Some(object) = sv.spawn(key.clone(), start_info, spawn_count + 1, backoff) {
                     sv.objects.insert(key.clone(), object)
                 } else {
-                    sv.objects.remove(&key).map(|(_, v)| v)
+                    sv.remove(&key, addr)
                 }
             } else {
                 debug!("actor won't be restarted");
-                sv.objects.remove(&key).map(|(_, v)| v)
-            }
-            .expect("where is the current actor?");
+                sv.remove(&key, addr)
+            };
+
+            // Passivated actors are removed in advance, see `passivate()`.
+            assert!(
+                removed.is_some() || is_passivated,
+                "where is the current actor?"
+            );
 
             // TODO: should we unregister the address right after failure?
             sv.context.book().remove(addr);
         };
 
-        let meta = Arc::new(ActorMeta {
-            group: self.meta.group.clone(),
-            key: key_str,
-        });
-
         let rt = self.rt_manager.get(&meta);
 
+        let sv = Arc::downgrade(self);
         let actor = Actor::new(
             meta.clone(),
             addr,
+            mailbox_params,
             self.termination_policy.clone(),
             self.status_subscription.clone(),
-        );
+            spawn_count,
+        )
+        .with_on_passivate(move || {
+            let sv = ward!(sv.upgrade());
+            sv.passivate(&key1, addr);
+        });
         entry.insert(Object::new(addr, actor));
 
         let scope = Scope::new(scope::trace_id(), addr, meta, self.scope_shared.clone())
@@ -418,6 +564,103 @@ where
         Some(object)
     }
 
+    /// Removes the actor if the key still belongs to it.
+    fn remove(&self, key: &R::Key, addr: Addr) -> Option<ObjectArc> {
+        let (_, object) = self.objects.remove_if(key, |_, o| o.addr() == addr)?;
+        self.start_order.remove(key);
+        Some(object)
+    }
+
+    /// Forgets the passivated actor before its mailbox is closed, so that
+    /// new messages start another actor instead of being lost.
+    fn passivate(&self, key: &R::Key, addr: Addr) {
+        // Inserted before removing to detect reactivation by any message.
+        self.passivated.insert(key.clone());
+
+        if self.remove(key, addr).is_none() {
+            self.passivated.remove(key);
+        }
+    }
+
+    /// Applies the group's strategy and intensity to the restart of the actor.
+    /// Returns `false` if the group has failed and the actor must not be
+    /// restarted.
+    fn on_restart(&self, key: &R::Key, policy: &RestartPolicy) -> bool {
+        if let Some(intensity) = policy.intensity {
+            let mut control = self.control.write();
+            let now = Instant::now();
+
+            let restarts = &mut control.restarts;
+            while restarts
+                .front()
+                .is_some_and(|t| now.duration_since(*t) >= intensity.period)
+            {
+                restarts.pop_front();
+            }
+            restarts.push_back(now);
+
+            if restarts.len() > intensity.max_restarts as usize {
+                drop(control);
+                error!(
+                    max_restarts = intensity.max_restarts,
+                    period = ?intensity.period,
+                    "restart intensity is exceeded, the group has failed"
+                );
+                self.fail();
+                return false;
+            }
+        }
+
+        let started_after = match policy.strategy {
+            SupervisionStrategy::OneForOne => return true,
+            SupervisionStrategy::OneForAll => 0,
+            SupervisionStrategy::RestForOne => {
+                ward!(self.start_order.get(key).map(|no| *no + 1), return true)
+            }
+        };
+
+        for item in self.objects.iter() {
+            let start_no = ward!(self.start_order.get(item.key()).map(|no| *no), continue);
+            if item.key() == key || start_no < started_after {
+                continue;
+            }
+
+            item.value()
+                .as_actor()
+                .expect("a supervisor stores only actors")
+                .request_restart();
+        }
+
+        true
+    }
+
+    /// Sends the message to the escalation group, see `Local::escalation()`.
+    fn escalate<M: Message>(&self, message: M) {
+        let addr = ward!(self.context.book().escalation());
+
+        // Escalations use the control lane, so it fails only if
+        // the escalation group is closed.
+        if let Err(err) = self.context.try_send_to(addr, message) {
+            warn!(error = %err, "cannot send to the escalation group");
+        }
+    }
+
+    /// Stops all actors of the group and terminates the system.
+    fn fail(&self) {
+        self.control.write().stop_spawning = true;
+
+        for item in self.objects.iter() {
+            item.value()
+                .as_actor()
+                .expect("a supervisor stores only actors")
+                .close();
+        }
+
+        if let Some(addr) = self.context.book().system_init() {
+            let _ = self.context.try_send_to(addr, GroupFailed);
+        }
+    }
+
     fn spawn_on_group_mounted(self: &Arc<Self>, outcome: Outcome<R::Key>) {
         let start_info = ActorStartInfo::on_group_mounted();
         match outcome {
@@ -457,6 +700,43 @@ where
         });
     }
 
+    fn mailbox_params(&self, system_config: &SystemConfig) -> MailboxParams {
+        // The config overrides the blueprint's parameters.
+        system_config.mailbox.make_params(&self.mailbox)
+    }
+
+    // It must be called without holding the control lock, because spawning
+    // actors holds locks of `objects` and takes the control lock inside.
+    fn update_mailboxes(&self) {
+        let params = self.mailbox_params(&self.control.read().system_config);
+
+        for item in self.objects.iter() {
+            let actor = item
+                .value()
+                .as_actor()
+                .expect("a supervisor stores only actors");
+
+            actor.configure_mailbox(params.clone());
+        }
+    }
+
+    // It must be called without holding the control lock.
+    fn terminate_unused(&self) {
+        // Unused keys cannot be reactivated anymore.
+        self.passivated.retain(|key| self.router.is_used(key));
+
+        for item in self.objects.iter() {
+            if self.router.is_used(item.key()) {
+                continue;
+            }
+
+            // `Terminate` uses the control lane, so it fails only if the actor is closed.
+            let _ = self
+                .context
+                .try_send_to(item.value().addr(), messages::Terminate::default());
+        }
+    }
+
     fn subscribe_to_statuses(&self, addr: Addr, forcing: bool) {
         // Firstly, add the subscriber to handle new objects right way.
         if !self.status_subscription.add(addr) && !forcing {
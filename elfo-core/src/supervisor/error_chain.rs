@@ -2,6 +2,21 @@ use std::{error::Error, fmt};
 
 pub(crate) struct ErrorChain<'a>(pub(crate) &'a dyn Error);
 
+impl ErrorChain<'_> {
+    /// Returns messages of the error and its sources.
+    pub(crate) fn to_vec(&self) -> Vec<String> {
+        let mut chain = vec![self.0.to_string()];
+
+        let mut cursor = self.0;
+        while let Some(err) = cursor.source() {
+            chain.push(err.to_string());
+            cursor = err;
+        }
+
+        chain
+    }
+}
+
 impl fmt::Display for ErrorChain<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)?;
@@ -31,4 +46,8 @@ fn error_chain() {
         format!("{}", ErrorChain(&*outer)),
         "outer: inner: innermost"
     );
+    assert_eq!(
+        ErrorChain(&*outer).to_vec(),
+        ["outer", "inner", "innermost"]
+    );
 }
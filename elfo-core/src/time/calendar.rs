@@ -0,0 +1,640 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::errors::ParseCalendarError;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+// Leap days can be 8 years apart, e.g. 2096-02-29 and 2104-02-29.
+const MAX_SEARCH_DAYS: i64 = 366 * 8 + 1;
+
+const MONTHS: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// Defines wall-clock times when [`Schedule`] emits messages.
+///
+/// Times are calculated in the local time of the UTC offset, which is set by
+/// [`Calendar::with_utc_offset()`] (UTC by default). To follow DST, the offset
+/// can be changed at provided times by [`Calendar::with_offset_transitions()`].
+///
+/// [`Schedule`]: super::Schedule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calendar {
+    rule: Rule,
+    offset: i32,
+    /// Sorted `(since, offset)` pairs, `since` is seconds since the epoch.
+    transitions: Vec<(i64, i32)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    Cron(Cron),
+    /// Sorted seconds since midnight.
+    Daily(Vec<u32>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cron {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // If both days and weekdays are restricted, either of them must match.
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl Calendar {
+    /// Parses a cron expression.
+    ///
+    /// Both the standard format (`minute hour day month weekday`) and the one
+    /// with seconds (`second minute hour day month weekday`) are supported.
+    /// Every field can contain `*`, numbers, ranges (`1-5`), steps (`*/15`,
+    /// `0-30/10`) and lists of them (`1,15,30`). Months and weekdays can also
+    /// be specified by names (`jan`, `mon`). Both `0` and `7` mean Sunday.
+    ///
+    /// If both the day and weekday fields are restricted (not `*`), the time
+    /// matches if either of them matches, like in cron.
+    ///
+    /// # Example
+    /// ```
+    /// # use elfo_core as elfo;
+    /// use elfo::time::Calendar;
+    ///
+    /// // At 00:00 every day.
+    /// let daily = Calendar::cron("0 0 * * *").unwrap();
+    /// // Every 10 seconds on weekdays.
+    /// let often = Calendar::cron("*/10 * * * * mon-fri").unwrap();
+    /// ```
+    pub fn cron(expr: &str) -> Result<Self, ParseCalendarError> {
+        let cron = Cron::parse(expr).map_err(|reason| ParseCalendarError { reason })?;
+
+        Ok(Self {
+            rule: Rule::Cron(cron),
+            offset: 0,
+            transitions: Vec::new(),
+        })
+    }
+
+    /// Creates a calendar of times of the day in the `HH:MM` or `HH:MM:SS`
+    /// format, repeated every day.
+    ///
+    /// # Example
+    /// ```
+    /// # use elfo_core as elfo;
+    /// use elfo::time::Calendar;
+    ///
+    /// let calendar = Calendar::daily(["09:30", "17:00"]).unwrap();
+    /// ```
+    pub fn daily<S: AsRef<str>>(
+        times: impl IntoIterator<Item = S>,
+    ) -> Result<Self, ParseCalendarError> {
+        let mut times = times
+            .into_iter()
+            .map(|time| parse_time_of_day(time.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|reason| ParseCalendarError { reason })?;
+
+        if times.is_empty() {
+            return Err(ParseCalendarError {
+                reason: "no times are provided".into(),
+            });
+        }
+
+        times.sort_unstable();
+        times.dedup();
+
+        Ok(Self {
+            rule: Rule::Daily(times),
+            offset: 0,
+            transitions: Vec::new(),
+        })
+    }
+
+    /// Sets the UTC offset in seconds, e.g. `3 * 3600` for UTC+3.
+    ///
+    /// # Panics
+    /// If the offset isn't less than 24 hours.
+    #[track_caller]
+    pub fn with_utc_offset(mut self, offset: i32) -> Self {
+        assert_offset(offset);
+        self.offset = offset;
+        self
+    }
+
+    /// Sets times when the UTC offset changes, e.g. when DST starts or ends.
+    /// Every item is a time and the offset in seconds used since that time.
+    /// The offset set by [`Calendar::with_utc_offset()`] is used before
+    /// the first transition.
+    ///
+    /// Times that are skipped when the clock moves forward aren't matched,
+    /// times that are repeated when the clock moves back are matched once.
+    ///
+    /// # Example
+    /// ```
+    /// # use elfo_core as elfo;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// use elfo::time::Calendar;
+    ///
+    /// // Europe/Berlin in 2024: CET (UTC+1), CEST (UTC+2) since March 31.
+    /// let calendar = Calendar::daily(["09:30"])
+    ///     .unwrap()
+    ///     .with_utc_offset(3600)
+    ///     .with_offset_transitions([
+    ///         (UNIX_EPOCH + Duration::from_secs(1_711_846_800), 2 * 3600),
+    ///         (UNIX_EPOCH + Duration::from_secs(1_729_990_800), 3600),
+    ///     ]);
+    /// ```
+    ///
+    /// # Panics
+    /// If any offset isn't less than 24 hours.
+    #[track_caller]
+    pub fn with_offset_transitions(
+        mut self,
+        transitions: impl IntoIterator<Item = (SystemTime, i32)>,
+    ) -> Self {
+        self.transitions = transitions
+            .into_iter()
+            .map(|(since, offset)| {
+                assert_offset(offset);
+                (to_secs(since), offset)
+            })
+            .collect();
+        self.transitions.sort_by_key(|(since, _)| *since);
+        self
+    }
+
+    /// Returns the first time strictly after the provided one, truncated to
+    /// seconds. Returns `None` if there is no such time, e.g. for
+    /// `0 0 30 2 *` (February 30).
+    pub fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        let mut from = to_secs(time) + 1;
+        // Local times never go back, so repeated times are matched once.
+        let mut local_from = i64::MIN;
+
+        let secs = loop {
+            let offset = i64::from(self.offset_at(from));
+            local_from = local_from.max(from + offset);
+            let found = self.rule.next(local_from)? - offset;
+
+            // If the offset changes before the found time, search again.
+            match self.next_transition(from) {
+                Some(since) if since <= found => from = since,
+                _ => break found,
+            }
+        };
+
+        let secs = u64::try_from(secs).ok()?;
+        Some(UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    fn offset_at(&self, secs: i64) -> i32 {
+        let index = self
+            .transitions
+            .partition_point(|(since, _)| *since <= secs);
+        match index.checked_sub(1) {
+            Some(index) => self.transitions[index].1,
+            None => self.offset,
+        }
+    }
+
+    fn next_transition(&self, secs: i64) -> Option<i64> {
+        let index = self
+            .transitions
+            .partition_point(|(since, _)| *since <= secs);
+        self.transitions.get(index).map(|(since, _)| *since)
+    }
+}
+
+#[track_caller]
+fn assert_offset(offset: i32) {
+    assert!(
+        i64::from(offset).abs() < SECS_PER_DAY,
+        "offset must be less than 24 hours"
+    );
+}
+
+fn to_secs(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(err) => -(err.duration().as_secs_f64().ceil() as i64),
+    }
+}
+
+impl Rule {
+    /// Returns the first matching local time (in seconds) not before `from`.
+    fn next(&self, from: i64) -> Option<i64> {
+        let first_day = from.div_euclid(SECS_PER_DAY);
+        let mut time = from.rem_euclid(SECS_PER_DAY) as u32;
+
+        for day in first_day..first_day + MAX_SEARCH_DAYS {
+            if self.matches_day(day) {
+                if let Some(time) = self.first_time(time) {
+                    return Some(day * SECS_PER_DAY + i64::from(time));
+                }
+            }
+
+            time = 0;
+        }
+
+        None
+    }
+
+    fn matches_day(&self, day: i64) -> bool {
+        match self {
+            Self::Cron(cron) => {
+                let (month, day_of_month) = month_day_from_days(day);
+                let weekday = (day + 4).rem_euclid(7) as u32; // 1970-01-01 is Thursday.
+
+                if !has(cron.months, month) {
+                    return false;
+                }
+
+                let day_matches = has(cron.days, day_of_month);
+                let weekday_matches = has(cron.weekdays, weekday);
+
+                if cron.any_day || cron.any_weekday {
+                    day_matches && weekday_matches
+                } else {
+                    day_matches || weekday_matches
+                }
+            }
+            Self::Daily(_) => true,
+        }
+    }
+
+    /// Returns the first matching time of the day not before `from`.
+    fn first_time(&self, from: u32) -> Option<u32> {
+        let cron = match self {
+            Self::Cron(cron) => cron,
+            Self::Daily(times) => return times.iter().find(|time| **time >= from).copied(),
+        };
+
+        let (hour, minute, second) = (from / 3600, from / 60 % 60, from % 60);
+        let first = |set| next_in(set, 0).expect("empty set");
+        let time = |h, m, s| h * 3600 + m * 60 + s;
+
+        let mut h = next_in(cron.hours, hour)?;
+        if h > hour {
+            return Some(time(h, first(cron.minutes), first(cron.seconds)));
+        }
+
+        let mut m = next_in(cron.minutes, minute);
+        while let Some(cur) = m {
+            if cur > minute {
+                return Some(time(h, cur, first(cron.seconds)));
+            }
+            if let Some(s) = next_in(cron.seconds, second) {
+                return Some(time(h, cur, s));
+            }
+            m = next_in(cron.minutes, cur + 1);
+        }
+
+        h = next_in(cron.hours, hour + 1)?;
+        Some(time(h, first(cron.minutes), first(cron.seconds)))
+    }
+}
+
+impl Cron {
+    fn parse(expr: &str) -> Result<Self, String> {
+        let fields = expr.split_whitespace().collect::<Vec<_>>();
+        let (seconds, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => return Err(format!("expected 5 or 6 fields, got {n}")),
+        };
+
+        let weekdays = parse_field(rest[4], 0, 7, WEEKDAYS)?;
+
+        Ok(Self {
+            seconds: parse_field(seconds, 0, 59, &[])?,
+            minutes: parse_field(rest[0], 0, 59, &[])?,
+            hours: parse_field(rest[1], 0, 23, &[])?,
+            days: parse_field(rest[2], 1, 31, &[])?,
+            months: parse_field(rest[3], 1, 12, MONTHS)?,
+            // Both 0 and 7 mean Sunday.
+            weekdays: (weekdays | weekdays >> 7) & 0x7f,
+            any_day: rest[2].starts_with('*'),
+            any_weekday: rest[4].starts_with('*'),
+        })
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |s: &str| {
+        let value = match names.iter().position(|name| name.eq_ignore_ascii_case(s)) {
+            Some(index) => index as u32 + min,
+            None => s.parse().map_err(|_| format!("invalid value `{s}`"))?,
+        };
+
+        if (min..=max).contains(&value) {
+            Ok(value)
+        } else {
+            Err(format!("`{s}` is out of range {min}-{max}"))
+        }
+    };
+
+    let mut set = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("invalid step `{step}`")),
+            },
+            None => (part, None),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            (start, if step.is_some() { max } else { start })
+        };
+
+        if start > end {
+            return Err(format!("invalid range `{range}`"));
+        }
+
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            set |= 1 << value;
+        }
+    }
+
+    Ok(set)
+}
+
+fn parse_time_of_day(time: &str) -> Result<u32, String> {
+    let invalid = || format!("invalid time `{time}`, expected `HH:MM` or `HH:MM:SS`");
+    let parse = |s: &str, max: u32| {
+        s.parse::<u32>()
+            .ok()
+            .filter(|value| s.len() == 2 && *value <= max)
+            .ok_or_else(invalid)
+    };
+
+    let parts = time.split(':').collect::<Vec<_>>();
+    let (h, m, s) = match parts[..] {
+        [h, m] => (parse(h, 23)?, parse(m, 59)?, 0),
+        [h, m, s] => (parse(h, 23)?, parse(m, 59)?, parse(s, 59)?),
+        _ => return Err(invalid()),
+    };
+
+    Ok(h * 3600 + m * 60 + s)
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Returns the first value in the set not less than `from`.
+fn next_in(set: u64, from: u32) -> Option<u32> {
+    let rest = set.checked_shr(from)?;
+    (rest != 0).then(|| from + rest.trailing_zeros())
+}
+
+/// Converts days since the epoch to `(month, day)`.
+/// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn month_day_from_days(days: i64) -> (u32, u32) {
+    let z = days + 719_468;
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses `YYYY-MM-DD HH:MM:SS` in UTC.
+    fn at(s: &str) -> SystemTime {
+        let parse = |range: std::ops::Range<usize>| s[range].parse::<i64>().unwrap();
+        let (y, m, d) = (parse(0..4), parse(5..7), parse(8..10));
+
+        // See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let y = if m <= 2 { y - 1 } else { y };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146_097 + doe - 719_468;
+
+        let secs = days * SECS_PER_DAY + parse(11..13) * 3600 + parse(14..16) * 60 + parse(17..19);
+        UNIX_EPOCH + Duration::from_secs(secs as u64)
+    }
+
+    fn next(calendar: &Calendar, after: &str) -> Option<SystemTime> {
+        calendar.next_after(at(after))
+    }
+
+    #[test]
+    fn cron_basic() {
+        let calendar = Calendar::cron("30 9 * * *").unwrap();
+        assert_eq!(
+            next(&calendar, "2024-03-10 09:29:59"),
+            Some(at("2024-03-10 09:30:00"))
+        );
+        assert_eq!(
+            next(&calendar, "2024-03-10 09:30:00"),
+            Some(at("2024-03-11 09:30:00"))
+        );
+        assert_eq!(
+            next(&calendar, "2024-12-31 23:59:59"),
+            Some(at("2025-01-01 09:30:00"))
+        );
+    }
+
+    #[test]
+    fn cron_steps_and_lists() {
+        let calendar = Calendar::cron("*/15 * * * *").unwrap();
+        assert_eq!(
+            next(&calendar, "2024-03-10 10:07:30"),
+            Some(at("2024-03-10 10:15:00"))
+        );
+        assert_eq!(
+            next(&calendar, "2024-03-10 23:45:00"),
+            Some(at("2024-03-11 00:00:00"))
+        );
+
+        let calendar = Calendar::cron("0 8-18/5,23 * * *").unwrap();
+        assert_eq!(
+            next(&calendar, "2024-03-10 08:00:00"),
+            Some(at("2024-03-10 13:00:00"))
+        );
+        assert_eq!(
+            next(&calendar, "2024-03-10 18:00:00"),
+            Some(at("2024-03-10 23:00:00"))
+        );
+
+        let calendar = Calendar::cron("*/10 * * * * *").unwrap();
+        assert_eq!(
+            next(&calendar, "2024-03-10 10:59:55"),
+            Some(at("2024-03-10 11:00:00"))
+        );
+        assert_eq!(
+            next(&calendar, "2024-03-10 11:00:00"),
+            Some(at("2024-03-10 11:00:10"))
+        );
+    }
+
+    #[test]
+    fn cron_days() {
+        // 2024-09-01 is Sunday.
+        let fridays = Calendar::cron("0 0 * * fri").unwrap();
+        assert_eq!(
+            next(&fridays, "2024-09-01 00:00:00"),
+            Some(at("2024-09-06 00:00:00"))
+        );
+
+        // The 13th or Friday.
+        let either = Calendar::cron("0 0 13 * 5").unwrap();
+        assert_eq!(
+            next(&either, "2024-09-06 00:00:00"),
+            Some(at("2024-09-13 00:00:00"))
+        );
+        assert_eq!(
+            next(&either, "2024-09-13 00:00:00"),
+            Some(at("2024-09-20 00:00:00"))
+        );
+
+        let sundays = Calendar::cron("0 0 * * 7").unwrap();
+        assert_eq!(
+            next(&sundays, "2024-09-01 00:00:00"),
+            Some(at("2024-09-08 00:00:00"))
+        );
+
+        let leap_days = Calendar::cron("0 0 29 feb *").unwrap();
+        assert_eq!(
+            next(&leap_days, "2024-03-01 00:00:00"),
+            Some(at("2028-02-29 00:00:00"))
+        );
+
+        let never = Calendar::cron("0 0 30 2 *").unwrap();
+        assert_eq!(next(&never, "2024-03-01 00:00:00"), None);
+    }
+
+    #[test]
+    fn cron_invalid() {
+        for expr in [
+            "",
+            "* * *",
+            "* * * * * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+            "* * * foo *",
+        ] {
+            assert!(Calendar::cron(expr).is_err(), "{expr}");
+        }
+    }
+
+    #[test]
+    fn daily() {
+        let calendar = Calendar::daily(["17:00", "09:30", "09:30:15"]).unwrap();
+        assert_eq!(
+            next(&calendar, "2024-03-10 09:30:00"),
+            Some(at("2024-03-10 09:30:15"))
+        );
+        assert_eq!(
+            next(&calendar, "2024-03-10 10:00:00"),
+            Some(at("2024-03-10 17:00:00"))
+        );
+        assert_eq!(
+            next(&calendar, "2024-03-10 17:00:00"),
+            Some(at("2024-03-11 09:30:00"))
+        );
+
+        let empty: [&str; 0] = [];
+        for times in [
+            &["24:00"][..],
+            &["9:30"],
+            &["09:60"],
+            &["09:30:00:00"],
+            &empty,
+        ] {
+            assert!(Calendar::daily(times).is_err(), "{times:?}");
+        }
+    }
+
+    #[test]
+    fn utc_offset() {
+        let calendar = Calendar::cron("0 0 * * *")
+            .unwrap()
+            .with_utc_offset(3 * 3600);
+        assert_eq!(
+            next(&calendar, "2024-03-10 12:00:00"),
+            Some(at("2024-03-10 21:00:00"))
+        );
+
+        let calendar = Calendar::daily(["23:00"])
+            .unwrap()
+            .with_utc_offset(-5 * 3600);
+        assert_eq!(
+            next(&calendar, "2024-03-10 12:00:00"),
+            Some(at("2024-03-11 04:00:00"))
+        );
+    }
+
+    #[test]
+    fn offset_transitions() {
+        // Europe/Berlin: CEST (UTC+2) since 2024-03-31 01:00 UTC (02:00 local),
+        // CET (UTC+1) since 2024-10-27 01:00 UTC (03:00 local).
+        let transitions = [
+            (at("2024-03-31 01:00:00"), 2 * 3600),
+            (at("2024-10-27 01:00:00"), 3600),
+        ];
+        let calendar = |times: &[&str]| {
+            Calendar::daily(times)
+                .unwrap()
+                .with_utc_offset(3600)
+                .with_offset_transitions(transitions)
+        };
+
+        let morning = calendar(&["09:30"]);
+        assert_eq!(
+            next(&morning, "2024-03-30 09:00:00"),
+            Some(at("2024-03-31 07:30:00"))
+        );
+        assert_eq!(
+            next(&morning, "2024-10-26 08:00:00"),
+            Some(at("2024-10-27 08:30:00"))
+        );
+        assert_eq!(
+            next(&morning, "2024-06-01 12:00:00"),
+            Some(at("2024-06-02 07:30:00"))
+        );
+
+        // Skipped times aren't matched.
+        let skipped = calendar(&["02:30", "04:00"]);
+        assert_eq!(
+            next(&skipped, "2024-03-31 00:00:00"),
+            Some(at("2024-03-31 02:00:00"))
+        );
+
+        // Repeated times are matched once.
+        let repeated = calendar(&["02:30", "04:00"]);
+        assert_eq!(
+            next(&repeated, "2024-10-26 23:00:00"),
+            Some(at("2024-10-27 00:30:00"))
+        );
+        assert_eq!(
+            next(&repeated, "2024-10-27 00:30:00"),
+            Some(at("2024-10-27 03:00:00"))
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    any::Any,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use pin_project::pin_project;
+use sealed::sealed;
+use tokio::time::{Duration, Instant, Sleep};
+
+use crate::{
+    addr::Addr,
+    envelope::{Envelope, MessageKind},
+    message::Message,
+    scope,
+    source::{SourceArc, SourceStream, UnattachedSource},
+    time::far_future,
+    tracing::TraceId,
+};
+
+/// A source that collapses bursts of messages pushed by the actor.
+/// The message is emitted once nothing has been pushed for `period`.
+///
+/// Depending on the constructor, either the last or the first message of
+/// the burst is emitted, others are dropped.
+///
+/// # Tracing
+///
+/// The emitted message continues the trace of the [`Debounce::push()`] call.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use elfo_core as elfo;
+/// # async fn exec(mut ctx: elfo::Context) {
+/// # use elfo::{message, msg};
+/// # #[message]
+/// # struct StateChanged;
+/// use elfo::time::Debounce;
+///
+/// #[message]
+/// struct Recalculate;
+///
+/// let debounce = ctx.attach(Debounce::last(Duration::from_millis(100)));
+///
+/// while let Some(envelope) = ctx.recv().await {
+///     msg!(match envelope {
+///         StateChanged => {
+///             debounce.push(Recalculate);
+///         },
+///         Recalculate => {
+///             tracing::info!("recalculating once per burst");
+///         },
+///     });
+/// }
+/// # }
+/// ```
+pub struct Debounce<M> {
+    source: SourceArc<DebounceSource<M>>,
+}
+
+#[sealed]
+impl<M: Message> crate::source::SourceHandle for Debounce<M> {
+    fn is_terminated(&self) -> bool {
+        self.source.lock().is_none()
+    }
+
+    fn terminate(self) {
+        ward!(self.source.lock()).terminate();
+    }
+}
+
+/// Which message of a burst or window is emitted.
+#[derive(Clone, Copy)]
+pub(super) enum Keep {
+    First,
+    Last,
+}
+
+impl Keep {
+    pub(super) fn apply<M>(self, pending: &mut Option<(M, TraceId)>, message: M) {
+        match self {
+            Keep::First if pending.is_some() => {}
+            _ => *pending = Some((message, scope::trace_id())),
+        }
+    }
+}
+
+#[pin_project]
+struct DebounceSource<M> {
+    period: Duration,
+    keep: Keep,
+    pending: Option<(M, TraceId)>,
+    #[pin]
+    sleep: Sleep,
+}
+
+impl<M: Message> Debounce<M> {
+    /// Creates an unattached instance of [`Debounce`], which emits the last
+    /// message of every burst.
+    pub fn last(period: Duration) -> UnattachedSource<Self> {
+        Self::new(period, Keep::Last)
+    }
+
+    /// Creates an unattached instance of [`Debounce`], which emits the first
+    /// message of every burst.
+    pub fn first(period: Duration) -> UnattachedSource<Self> {
+        Self::new(period, Keep::First)
+    }
+
+    fn new(period: Duration, keep: Keep) -> UnattachedSource<Self> {
+        let source = DebounceSource {
+            period,
+            keep,
+            pending: None,
+            sleep: tokio::time::sleep_until(far_future()),
+        };
+
+        let source = SourceArc::new(source, false);
+        UnattachedSource::new(source, |source| Self { source })
+    }
+
+    /// Pushes the message and restarts the quiet period.
+    pub fn push(&self, message: M) {
+        let mut guard = ward!(self.source.lock());
+        let source = guard.stream().project();
+
+        source.keep.apply(source.pending, message);
+        source.sleep.reset(Instant::now() + *source.period);
+        guard.wake();
+    }
+}
+
+impl<M: Message> SourceStream for DebounceSource<M> {
+    fn as_any_mut(self: Pin<&mut Self>) -> Pin<&mut dyn Any> {
+        // SAFETY: we only cast here, it cannot move data.
+        unsafe { self.map_unchecked_mut(|s| s) }
+    }
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>> {
+        let this = self.project();
+
+        // Do nothing if there is nothing to emit.
+        if this.pending.is_none() {
+            return Poll::Pending;
+        }
+
+        // Wait for the end of the quiet period.
+        if !this.sleep.poll(cx).is_ready() {
+            return Poll::Pending;
+        }
+
+        // Emit the message.
+        let (message, trace_id) = this.pending.take().unwrap();
+        let kind = MessageKind::Regular { sender: Addr::NULL };
+        let envelope = Envelope::with_trace_id(message, kind, trace_id).upcast();
+
+        Poll::Ready(Some(envelope))
+    }
+}
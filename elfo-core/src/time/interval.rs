@@ -5,6 +5,7 @@ use std::{
     task::{self, Poll},
 };
 
+use metrics::counter;
 use pin_project::pin_project;
 use sealed::sealed;
 use tokio::time::{Duration, Instant, Sleep};
@@ -13,7 +14,7 @@ use crate::{
     envelope::{Envelope, MessageKind},
     message::Message,
     source::{SourceArc, SourceStream, UnattachedSource},
-    time::far_future,
+    time::{far_future, random_jitter},
     tracing::TraceId,
     Addr,
 };
@@ -69,13 +70,71 @@ impl<M: Message> crate::source::SourceHandle for Interval<M> {
     }
 }
 
+/// Defines the behavior of [`Interval`] when ticks are missed, e.g. because
+/// the actor is busy for longer than the period. The semantics is the same as
+/// [`tokio::time::MissedTickBehavior`].
+///
+/// For instance, if the period is 10ms and the actor is busy until 35ms,
+/// ticks are emitted as follows:
+///
+/// ```text
+/// Burst: 35ms, 35ms, 35ms, 40ms, 50ms, ...
+/// Delay: 35ms, 45ms, 55ms, ...
+/// Skip:  35ms, 40ms, 50ms, ...
+/// ```
+///
+/// Ticks aren't considered missed if they are late by less than 5ms.
+/// Ticks skipped by [`Delay`] and [`Skip`] policies are counted by the
+/// `elfo_skipped_ticks_total` metric.
+///
+/// [`Delay`]: MissedTickPolicy::Delay
+/// [`Skip`]: MissedTickPolicy::Skip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum MissedTickPolicy {
+    /// Emits all missed ticks as fast as possible, then returns to the
+    /// original schedule.
+    #[default]
+    Burst,
+    /// Emits one tick and schedules next ones from the current time.
+    Delay,
+    /// Emits one tick and schedules next ones to the nearest tick of
+    /// the original schedule.
+    Skip,
+}
+
+impl MissedTickPolicy {
+    /// Returns the next deadline and the number of skipped ticks.
+    fn next(self, deadline: Instant, now: Instant, period: Duration) -> (Instant, u64) {
+        let missed = now - deadline;
+        let skipped = (missed.as_nanos() / period.as_nanos()) as u64;
+
+        match self {
+            Self::Burst => (deadline + period, 0),
+            Self::Delay => (now + period, skipped),
+            Self::Skip => {
+                let rest = missed.as_nanos() % period.as_nanos();
+                (now + period - Duration::from_nanos(rest as u64), skipped)
+            }
+        }
+    }
+}
+
 const NEVER: Duration = Duration::ZERO;
 
+// The same threshold is used by `tokio::time::Interval`.
+const MISSED_TICK_THRESHOLD: Duration = Duration::from_millis(5);
+
 #[pin_project]
 struct IntervalSource<M> {
     message: M,
     period: Duration,
     is_delayed: bool,
+    policy: MissedTickPolicy,
+    /// The maximum random shift of every tick.
+    tick_jitter: Duration,
+    /// The random shift of the current deadline.
+    offset: Duration,
     #[pin]
     sleep: Sleep,
 }
@@ -87,6 +146,9 @@ impl<M: Message> Interval<M> {
             message,
             period: NEVER,
             is_delayed: false,
+            policy: MissedTickPolicy::default(),
+            tick_jitter: Duration::ZERO,
+            offset: Duration::ZERO,
             sleep: tokio::time::sleep_until(far_future()),
         };
 
@@ -100,7 +162,31 @@ impl<M: Message> Interval<M> {
         *guard.stream().project().message = message;
     }
 
-    // TODO: pub fn set_missed_tick_policy
+    /// Configures the behavior when ticks are missed, see
+    /// [`MissedTickPolicy`] for details. [`MissedTickPolicy::Burst`] is used
+    /// by default.
+    ///
+    /// The policy is preserved when the timer is rescheduled.
+    pub fn set_missed_tick_policy(&self, policy: MissedTickPolicy) {
+        let mut guard = ward!(self.source.lock());
+        *guard.stream().project().policy = policy;
+    }
+
+    /// Randomly shifts every tick by up to `jitter` without changing the
+    /// schedule, i.e. the `n`-th tick is emitted at a random moment between
+    /// `start + n * period` and `start + n * period + jitter`.
+    ///
+    /// It's useful to avoid bursts of load if many actors have intervals with
+    /// the same period. The jitter should be less than the period.
+    /// Use [`Duration::ZERO`] (by default) to disable shifts.
+    ///
+    /// The generator can be seeded by [`seed_jitter()`] in tests.
+    ///
+    /// [`seed_jitter()`]: super::seed_jitter()
+    pub fn set_tick_jitter(&self, jitter: Duration) {
+        let mut guard = ward!(self.source.lock());
+        *guard.stream().project().tick_jitter = jitter;
+    }
 
     /// Configures the period of ticks. Intended to be called on
     /// `ConfigUpdated`.
@@ -160,6 +246,26 @@ impl<M: Message> Interval<M> {
         self.schedule(None, period);
     }
 
+    /// Schedules the timer to start emitting ticks every `period`.
+    /// The first tick will be emitted after `period` shifted by a random delay
+    /// up to `jitter`, which is kept for next ticks.
+    ///
+    /// It's useful to avoid bursts of load if many actors start intervals
+    /// with the same period simultaneously, e.g. on startup.
+    ///
+    /// Reschedules the timer if it's already started.
+    /// The generator can be seeded by [`seed_jitter()`] in tests.
+    ///
+    /// # Panics
+    ///
+    /// If `period` is zero.
+    ///
+    /// [`seed_jitter()`]: super::seed_jitter()
+    #[track_caller]
+    pub fn start_with_jitter(&self, period: Duration, jitter: Duration) {
+        self.start_after(period + random_jitter(jitter), period);
+    }
+
     /// Schedules the timer to start emitting ticks every `period`.
     /// The first tick will be emitted after `delay`.
     ///
@@ -209,9 +315,10 @@ impl<M: Message> Interval<M> {
 
         *source.is_delayed = when.is_some();
         *source.period = period;
+        *source.offset = random_jitter(*source.tick_jitter);
 
         let new_deadline = when.unwrap_or_else(|| Instant::now() + period);
-        source.sleep.reset(new_deadline);
+        source.sleep.reset(new_deadline + *source.offset);
         guard.wake();
     }
 }
@@ -238,11 +345,23 @@ impl<M: Message> SourceStream for IntervalSource<M> {
         // After first tick, the interval isn't delayed even if it was.
         *this.is_delayed = false;
 
-        // Reset the underlying timer.
+        // Reset the underlying timer according to the missed tick policy.
         // It would be nice to use `reset_without_reregister` here, but it's private.
         // TODO: consider moving to `tokio::time::Interval`, which uses it internally.
-        let new_deadline = this.sleep.deadline() + *this.period;
-        this.sleep.reset(new_deadline);
+        // Jitter doesn't affect the schedule.
+        let deadline = this.sleep.deadline() - *this.offset;
+        let now = Instant::now();
+        let new_deadline = if now > deadline + *this.offset + MISSED_TICK_THRESHOLD {
+            let (new_deadline, skipped) = this.policy.next(deadline, now, *this.period);
+            if skipped > 0 {
+                counter!("elfo_skipped_ticks_total", skipped, "message" => this.message.name());
+            }
+            new_deadline
+        } else {
+            deadline + *this.period
+        };
+        *this.offset = random_jitter(*this.tick_jitter);
+        this.sleep.reset(new_deadline + *this.offset);
 
         // Emit the message.
         let message = this.message.clone();
@@ -1,13 +1,27 @@
-use std::time::{Duration, SystemTime};
+use std::{
+    cell::RefCell,
+    time::{Duration, SystemTime},
+};
 
 use tokio::time::Instant;
 
-pub use self::{delay::Delay, interval::Interval};
+pub use self::{
+    calendar::Calendar,
+    debounce::Debounce,
+    delay::Delay,
+    interval::{Interval, MissedTickPolicy},
+    schedule::Schedule,
+    throttle::Throttle,
+};
 
 pub(crate) use r#impl::*;
 
+mod calendar;
+mod debounce;
 mod delay;
 mod interval;
+mod schedule;
+mod throttle;
 
 fn far_future() -> Instant {
     // Copied from `tokio`.
@@ -18,6 +32,30 @@ fn far_future() -> Instant {
     Instant::now() + Duration::from_secs(86400 * 365 * 30)
 }
 
+thread_local! {
+    static RNG: RefCell<fastrand::Rng> = RefCell::new(fastrand::Rng::new());
+}
+
+/// Returns a random duration in `[0, max]`.
+fn random_jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return max;
+    }
+
+    let max = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+    Duration::from_nanos(RNG.with(|rng| rng.borrow_mut().u64(0..=max)))
+}
+
+/// Seeds the random generator of the current thread used for jitter, see
+/// [`Interval::start_with_jitter()`] and [`Interval::set_tick_jitter()`].
+///
+/// Tests use the current thread runtime by default, so seeding makes
+/// jittered intervals deterministic.
+#[cfg(feature = "test-util")]
+pub fn seed_jitter(seed: u64) {
+    RNG.with(|rng| rng.borrow_mut().seed(seed));
+}
+
 #[cfg(test)]
 pub(crate) mod r#impl {
     use std::{cell::Cell, time::Duration};
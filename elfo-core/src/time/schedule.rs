@@ -0,0 +1,269 @@
+use std::{
+    any::Any,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+    time::SystemTime,
+};
+
+use pin_project::pin_project;
+use sealed::sealed;
+use tokio::time::{Duration, Instant, Sleep};
+
+use crate::{
+    envelope::{Envelope, MessageKind},
+    message::Message,
+    source::{SourceArc, SourceStream, UnattachedSource},
+    time::{now, Calendar},
+    tracing::TraceId,
+    Addr,
+};
+
+/// A source that emits messages at wall-clock times defined by [`Calendar`],
+/// e.g. by a cron expression. Clones the message every time.
+///
+/// Unlike [`Interval`], it follows the system clock, which is rechecked
+/// at least every second. If the clock jumps forward over several times,
+/// the message is emitted only once. If the clock jumps backward,
+/// already emitted times aren't repeated.
+///
+/// [`Interval`]: super::Interval
+///
+/// # Tracing
+///
+/// Every message starts a new trace, thus a new trace id is generated and
+/// assigned to the current scope.
+///
+/// # Example
+///
+/// ```
+/// # use elfo_core as elfo;
+/// # struct Config { settlement: String }
+/// # async fn exec(mut ctx: elfo::Context<Config>) {
+/// # use elfo::{message, msg};
+/// use elfo::{
+///     messages::ConfigUpdated,
+///     time::{Calendar, Schedule},
+/// };
+///
+/// #[message]
+/// struct Settle;
+///
+/// let calendar = |expr: &str| Calendar::cron(expr).unwrap().with_utc_offset(3 * 3600);
+/// let schedule = ctx.attach(Schedule::new(calendar(&ctx.config().settlement), Settle));
+///
+/// while let Some(envelope) = ctx.recv().await {
+///     msg!(match envelope {
+///         ConfigUpdated => {
+///             schedule.set_calendar(calendar(&ctx.config().settlement));
+///         },
+///         Settle => {
+///             tracing::info!("settlement!");
+///         },
+///     });
+/// }
+/// # }
+/// ```
+pub struct Schedule<M> {
+    source: SourceArc<ScheduleSource<M>>,
+}
+
+#[sealed]
+impl<M: Message> crate::source::SourceHandle for Schedule<M> {
+    fn is_terminated(&self) -> bool {
+        self.source.lock().is_none()
+    }
+
+    fn terminate(self) {
+        ward!(self.source.lock()).terminate();
+    }
+}
+
+// The system clock can jump, so it's rechecked at least this often.
+const MAX_SLEEP: Duration = Duration::from_secs(1);
+
+#[pin_project]
+struct ScheduleSource<M> {
+    message: M,
+    calendar: Calendar,
+    /// `None` if there are no more times.
+    next: Option<SystemTime>,
+    #[pin]
+    sleep: Sleep,
+}
+
+impl<M: Message> Schedule<M> {
+    /// Creates an unattached instance of [`Schedule`].
+    pub fn new(calendar: Calendar, message: M) -> UnattachedSource<Self> {
+        let source = ScheduleSource {
+            message,
+            next: calendar.next_after(now()),
+            calendar,
+            sleep: tokio::time::sleep_until(Instant::now()),
+        };
+
+        let source = SourceArc::new(source, false);
+        UnattachedSource::new(source, |source| Self { source })
+    }
+
+    /// Replaces a stored message with the provided one.
+    pub fn set_message(&self, message: M) {
+        let mut guard = ward!(self.source.lock());
+        *guard.stream().project().message = message;
+    }
+
+    /// Replaces the calendar. Intended to be called on `ConfigUpdated`.
+    ///
+    /// Next times are calculated from the current time, so the message isn't
+    /// emitted for times between the last emitted one and now.
+    pub fn set_calendar(&self, calendar: Calendar) {
+        let mut guard = ward!(self.source.lock());
+        let source = guard.stream().project();
+
+        *source.next = calendar.next_after(now());
+        *source.calendar = calendar;
+        source.sleep.reset(Instant::now());
+        guard.wake();
+    }
+}
+
+impl<M: Message> SourceStream for ScheduleSource<M> {
+    fn as_any_mut(self: Pin<&mut Self>) -> Pin<&mut dyn Any> {
+        // SAFETY: we only cast here, it cannot move data.
+        unsafe { self.map_unchecked_mut(|s| s) }
+    }
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>> {
+        let mut this = self.project();
+
+        let (next, now) = loop {
+            // Do nothing if there are no more times.
+            let Some(next) = *this.next else {
+                return Poll::Pending;
+            };
+
+            // Wait for a tick from implementation.
+            if !this.sleep.as_mut().poll(cx).is_ready() {
+                return Poll::Pending;
+            }
+
+            // The timer is based on the monotonic clock, so the system clock
+            // is checked to detect jumps and wait for the rest of the time.
+            let now = now();
+            match next.duration_since(now) {
+                Ok(rest) if !rest.is_zero() => {
+                    this.sleep
+                        .as_mut()
+                        .reset(Instant::now() + rest.min(MAX_SLEEP));
+                }
+                _ => break (next, now),
+            }
+        };
+
+        // Schedule the next time. Times missed because of clock jumps are skipped.
+        let next = this.calendar.next_after(now.max(next));
+        if let Some(next) = next {
+            let rest = next.duration_since(now).unwrap_or_default();
+            this.sleep.reset(Instant::now() + rest.min(MAX_SLEEP));
+        }
+        *this.next = next;
+
+        // Emit the message.
+        let message = this.message.clone();
+        let kind = MessageKind::Regular { sender: Addr::NULL };
+        let trace_id = TraceId::generate();
+        let envelope = Envelope::with_trace_id(message, kind, trace_id).upcast();
+
+        Poll::Ready(Some(envelope))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::task::noop_waker_ref;
+
+    use super::*;
+    use crate::{message, time};
+
+    #[message]
+    struct Tick;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    /// Advances both the monotonic and system clocks.
+    async fn advance(duration: Duration) {
+        tokio::time::advance(duration).await;
+        time::advance(duration);
+    }
+
+    fn poll(source: &mut Pin<Box<ScheduleSource<Tick>>>) -> bool {
+        let mut cx = task::Context::from_waker(noop_waker_ref());
+        match source.as_mut().poll_recv(&mut cx) {
+            Poll::Ready(envelope) => envelope.is_some(),
+            Poll::Pending => false,
+        }
+    }
+
+    fn source(calendar: Calendar) -> Pin<Box<ScheduleSource<Tick>>> {
+        Box::pin(ScheduleSource {
+            message: Tick,
+            next: calendar.next_after(now()),
+            calendar,
+            sleep: tokio::time::sleep_until(Instant::now()),
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn emits_at_times() {
+        // Every hour at HH:30.
+        let mut source = source(Calendar::cron("30 * * * *").unwrap());
+
+        for _ in 0..3 {
+            advance(HOUR / 2 - Duration::from_secs(1)).await;
+            assert!(!poll(&mut source));
+            advance(Duration::from_secs(1)).await;
+            assert!(poll(&mut source));
+            assert!(!poll(&mut source));
+            advance(HOUR / 2).await;
+            assert!(!poll(&mut source));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn clock_jumps_forward() {
+        let mut source = source(Calendar::cron("30 * * * *").unwrap());
+
+        // Several times are skipped, but the message is emitted once.
+        tokio::time::advance(Duration::from_secs(1)).await;
+        time::advance(5 * HOUR);
+        assert!(poll(&mut source));
+        assert!(!poll(&mut source));
+
+        // Now is 05:00, the next time is 05:30.
+        advance(HOUR / 2 - Duration::from_secs(1)).await;
+        assert!(!poll(&mut source));
+        advance(Duration::from_secs(1)).await;
+        assert!(poll(&mut source));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn clock_jumps_backward() {
+        time::advance(10 * HOUR);
+        let mut source = source(Calendar::cron("30 * * * *").unwrap());
+
+        advance(HOUR / 2).await;
+        assert!(poll(&mut source));
+
+        // Now is 08:30, the emitted 10:30 isn't repeated.
+        tokio::time::advance(Duration::from_secs(1)).await;
+        time::NOW.with(|now| now.set(now.get() - 2 * HOUR));
+        for _ in 0..(3 * 3600 - 1) {
+            advance(Duration::from_secs(1)).await;
+            assert!(!poll(&mut source));
+        }
+
+        // The next time is 11:30.
+        advance(Duration::from_secs(1)).await;
+        assert!(poll(&mut source));
+    }
+}
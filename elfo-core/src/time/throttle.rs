@@ -0,0 +1,156 @@
+use std::{
+    any::Any,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use pin_project::pin_project;
+use sealed::sealed;
+use tokio::time::{Duration, Instant, Sleep};
+
+use crate::{
+    addr::Addr,
+    envelope::{Envelope, MessageKind},
+    message::Message,
+    source::{SourceArc, SourceStream, UnattachedSource},
+    time::{debounce::Keep, far_future},
+    tracing::TraceId,
+};
+
+/// A source that emits messages pushed by the actor at most once per `window`.
+///
+/// A message pushed outside of a window is emitted immediately and opens
+/// a new window. Messages pushed inside the window are collapsed into one,
+/// which is emitted at the end of the window and opens the next one.
+/// Depending on the constructor, either the last or the first of them is
+/// emitted, others are dropped.
+///
+/// # Tracing
+///
+/// The emitted message continues the trace of the [`Throttle::push()`] call.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use elfo_core as elfo;
+/// # async fn exec(mut ctx: elfo::Context) {
+/// # use elfo::{message, msg};
+/// # #[message]
+/// # struct PriceChanged(f64);
+/// use elfo::time::Throttle;
+///
+/// #[message]
+/// struct Publish(f64);
+///
+/// let throttle = ctx.attach(Throttle::last(Duration::from_secs(1)));
+///
+/// while let Some(envelope) = ctx.recv().await {
+///     msg!(match envelope {
+///         PriceChanged(price) => {
+///             throttle.push(Publish(price));
+///         },
+///         Publish(price) => {
+///             tracing::info!(price, "at most once per second");
+///         },
+///     });
+/// }
+/// # }
+/// ```
+pub struct Throttle<M> {
+    source: SourceArc<ThrottleSource<M>>,
+}
+
+#[sealed]
+impl<M: Message> crate::source::SourceHandle for Throttle<M> {
+    fn is_terminated(&self) -> bool {
+        self.source.lock().is_none()
+    }
+
+    fn terminate(self) {
+        ward!(self.source.lock()).terminate();
+    }
+}
+
+#[pin_project]
+struct ThrottleSource<M> {
+    window: Duration,
+    keep: Keep,
+    pending: Option<(M, TraceId)>,
+    is_window_open: bool,
+    #[pin]
+    sleep: Sleep,
+}
+
+impl<M: Message> Throttle<M> {
+    /// Creates an unattached instance of [`Throttle`], which emits the last
+    /// message pushed inside a window.
+    pub fn last(window: Duration) -> UnattachedSource<Self> {
+        Self::new(window, Keep::Last)
+    }
+
+    /// Creates an unattached instance of [`Throttle`], which emits the first
+    /// message pushed inside a window.
+    pub fn first(window: Duration) -> UnattachedSource<Self> {
+        Self::new(window, Keep::First)
+    }
+
+    fn new(window: Duration, keep: Keep) -> UnattachedSource<Self> {
+        let source = ThrottleSource {
+            window,
+            keep,
+            pending: None,
+            is_window_open: false,
+            sleep: tokio::time::sleep_until(far_future()),
+        };
+
+        let source = SourceArc::new(source, false);
+        UnattachedSource::new(source, |source| Self { source })
+    }
+
+    /// Pushes the message to be emitted now or at the end of the window.
+    pub fn push(&self, message: M) {
+        let mut guard = ward!(self.source.lock());
+        let source = guard.stream().project();
+
+        source.keep.apply(source.pending, message);
+
+        // Emit the message immediately if there is no open window.
+        if !*source.is_window_open {
+            *source.is_window_open = true;
+            source.sleep.reset(Instant::now());
+            guard.wake();
+        }
+    }
+}
+
+impl<M: Message> SourceStream for ThrottleSource<M> {
+    fn as_any_mut(self: Pin<&mut Self>) -> Pin<&mut dyn Any> {
+        // SAFETY: we only cast here, it cannot move data.
+        unsafe { self.map_unchecked_mut(|s| s) }
+    }
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>> {
+        let mut this = self.project();
+
+        // Wait for the end of the window.
+        if !this.sleep.as_mut().poll(cx).is_ready() {
+            return Poll::Pending;
+        }
+
+        // Close the window if nothing has been pushed inside it.
+        let Some((message, trace_id)) = this.pending.take() else {
+            *this.is_window_open = false;
+            this.sleep.reset(far_future());
+            return Poll::Pending;
+        };
+
+        // Emit the message and open the next window.
+        this.sleep.reset(Instant::now() + *this.window);
+        let kind = MessageKind::Regular { sender: Addr::NULL };
+        let envelope = Envelope::with_trace_id(message, kind, trace_id).upcast();
+
+        Poll::Ready(Some(envelope))
+    }
+}
@@ -1,5 +1,6 @@
-use std::{cell::RefCell, sync::Arc};
+use std::sync::Arc;
 
+use fxhash::FxHashMap;
 use parking_lot::RwLock;
 use sealed::sealed;
 use tokio::runtime::Handle;
@@ -13,6 +14,7 @@ use crate::{
     demux::Demux,
     envelope::Envelope,
     group::Blueprint,
+    init,
     object::Object,
     runtime::RuntimeManager,
 };
@@ -29,7 +31,11 @@ pub struct Topology {
 
 struct Inner {
     last_group_no: u8,
+    /// Numbers of unmounted groups, reused by new ones.
+    free_group_nos: Vec<GroupNo>,
     locals: Vec<LocalActorGroup>,
+    /// Routes of local groups, shared with their contexts.
+    demuxes: FxHashMap<Addr, Demux>,
     #[cfg(feature = "network")]
     remotes: Vec<RemoteActorGroup>,
     connections: Vec<Connection>,
@@ -40,7 +46,9 @@ impl Default for Inner {
     fn default() -> Self {
         Self {
             last_group_no: SYSTEM_INIT_GROUP_NO,
+            free_group_nos: Vec::new(),
             locals: Vec::new(),
+            demuxes: FxHashMap::default(),
             #[cfg(feature = "network")]
             remotes: Vec::new(),
             connections: Vec::new(),
@@ -85,6 +93,21 @@ impl ConnectionTo {
             Self::Remote(name) => Some(name),
         }
     }
+
+    fn is_local(&self, addr: Addr) -> bool {
+        matches!(self, Self::Local(to) if *to == addr)
+    }
+}
+
+impl Inner {
+    #[track_caller]
+    fn local_addr(&self, name: &str) -> Addr {
+        self.locals
+            .iter()
+            .find(|group| group.name == name)
+            .unwrap_or_else(|| panic!("local group `{name}` not found"))
+            .addr
+    }
 }
 
 impl Default for Topology {
@@ -125,6 +148,14 @@ impl Topology {
 
     /// Declares a new local group.
     ///
+    /// Groups can be declared and mounted while the system is running.
+    /// Such groups receive configs on the next config reload, e.g. after
+    /// `ReloadConfigs` is sent to `elfo_configurer`. Routes from other groups
+    /// to such groups are added by [`Topology::add_route()`].
+    ///
+    /// Numbers of unmounted groups are reused, so only mounted groups are
+    /// limited by the number of available addresses.
+    ///
     /// # Panics
     /// * If the name is already taken for another local group.
     /// * If there are too many local groups.
@@ -139,23 +170,141 @@ impl Topology {
             }
         }
 
-        inner.last_group_no = inner.last_group_no.checked_add(1).expect("too many groups");
-        let group_no = GroupNo::new(inner.last_group_no, self.launch_id).expect("invalid group no");
+        let group_no = match inner.free_group_nos.pop() {
+            Some(group_no) => group_no,
+            None => {
+                inner.last_group_no = inner.last_group_no.checked_add(1).expect("too many groups");
+                GroupNo::new(inner.last_group_no, self.launch_id).expect("invalid group no")
+            }
+        };
 
         let entry = self.book.vacant_entry(group_no);
+        let demux = Demux::default();
         inner.locals.push(LocalActorGroup {
             addr: entry.addr(),
             name: name.clone(),
             is_entrypoint: false,
             stop_order: 0,
         });
+        inner.demuxes.insert(entry.addr(), demux.clone());
 
         Local {
             name,
             topology: self,
             entry,
-            demux: RefCell::new(Demux::default()),
+            demux,
+        }
+    }
+
+    /// Terminates the local group and removes it from the topology.
+    ///
+    /// The group is terminated in the same way as on the system termination,
+    /// then its address becomes invalid and all connections and routes to and
+    /// from it are removed. It's intended to remove groups mounted at runtime.
+    ///
+    /// # Panics
+    /// If there is no local group with the given name.
+    pub async fn unmount(&self, name: &str) {
+        let group = self
+            .locals()
+            .find(|group| group.name == name)
+            .unwrap_or_else(|| panic!("local group `{name}` not found"));
+
+        let addr = group.addr;
+        let ctx = Context::new(self.book.clone(), Demux::default());
+        init::stop_group(&ctx, group).await;
+
+        let mut inner = self.inner.write();
+        inner.locals.retain(|group| group.addr != addr);
+        inner.demuxes.remove(&addr);
+        for demux in inner.demuxes.values() {
+            demux.remove_local(addr);
+        }
+        inner
+            .connections
+            .retain(|conn| conn.from != addr && !conn.to.is_local(addr));
+        drop(inner);
+
+        self.book.remove(addr);
+
+        // Only after removing the address to avoid collisions with new groups.
+        let group_no = addr.group_no().expect("invalid group addr");
+        self.inner.write().free_group_nos.push(group_no);
+    }
+
+    /// Adds a route between local groups, see `Local::route_to()`.
+    ///
+    /// Unlike `Local::route_to()`, it can be used while the system is running.
+    /// Envelopes being sent concurrently are routed either by old or new
+    /// routes, but never by a mix of them.
+    ///
+    /// # Panics
+    /// If there is no local group with any of the given names.
+    #[track_caller]
+    pub fn add_route<F>(&self, from: &str, to: &str, filter: F)
+    where
+        F: Fn(&Envelope) -> bool + Send + Sync + 'static,
+    {
+        let mut inner = self.inner.write();
+        let (from, to) = (inner.local_addr(from), inner.local_addr(to));
+
+        inner.demuxes[&from].append_local(to, filter);
+        inner.connections.push(Connection {
+            from,
+            to: ConnectionTo::Local(to),
+        });
+    }
+
+    /// Removes all routes from the `from` local group to the `to` one.
+    /// Returns `false` if there are no such routes.
+    ///
+    /// # Panics
+    /// If there is no local group with any of the given names.
+    #[track_caller]
+    pub fn remove_route(&self, from: &str, to: &str) -> bool {
+        let mut inner = self.inner.write();
+        let (from, to) = (inner.local_addr(from), inner.local_addr(to));
+
+        if !inner.demuxes[&from].remove_local(to) {
+            return false;
         }
+
+        inner
+            .connections
+            .retain(|conn| conn.from != from || !conn.to.is_local(to));
+        true
+    }
+
+    /// Atomically redirects all routes from the `from` local group to the `old`
+    /// one to the `new` one, keeping their filters. Returns `false` if there
+    /// are no such routes.
+    ///
+    /// It allows to swap a downstream without restarting the node, e.g.
+    /// ```
+    /// # use elfo_core as elfo;
+    /// # fn exec(topology: elfo::Topology) {
+    /// topology.replace_route("strategy", "exchange", "simulator");
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    /// If there is no local group with any of the given names.
+    #[track_caller]
+    pub fn replace_route(&self, from: &str, old: &str, new: &str) -> bool {
+        let mut inner = self.inner.write();
+        let from = inner.local_addr(from);
+        let (old, new) = (inner.local_addr(old), inner.local_addr(new));
+
+        if !inner.demuxes[&from].replace_local(old, new) {
+            return false;
+        }
+
+        for conn in &mut inner.connections {
+            if conn.from == from && conn.to.is_local(old) {
+                conn.to = ConnectionTo::Local(new);
+            }
+        }
+        true
     }
 
     /// Returns an iterator over all local groups.
@@ -177,7 +326,7 @@ pub struct Local<'t> {
     topology: &'t Topology,
     name: String,
     entry: VacantEntry<'t>,
-    demux: RefCell<Demux>,
+    demux: Demux,
 }
 
 impl<'t> Local<'t> {
@@ -197,6 +346,40 @@ impl<'t> Local<'t> {
         self
     }
 
+    /// Mark this group as the escalation group.
+    ///
+    /// It means, that `ActorFailed` and `RestartsExhausted` messages about
+    /// actors of all groups are sent to this group.
+    ///
+    /// # Panics
+    /// If another group is already marked as the escalation group.
+    #[track_caller]
+    pub fn escalation(self) -> Self {
+        if !self.topology.book.set_escalation(self.entry.addr()) {
+            panic!("the escalation group is already set");
+        }
+        self
+    }
+
+    /// Mark this group as the deadletter group.
+    ///
+    /// It means, that messages sent by `Context::send()`, `try_send()`,
+    /// `send_to()` and `try_send_to()`, which haven't reached any mailbox
+    /// (e.g. discarded by routers or sent to closed mailboxes and dead
+    /// addresses), are sent to this group as `DeadLetter`. At most 100 dead
+    /// letters per second of each message type are sent, all of them are
+    /// counted by the `elfo_dead_letters_total` metric.
+    ///
+    /// # Panics
+    /// If another group is already marked as the deadletter group.
+    #[track_caller]
+    pub fn deadletter(self) -> Self {
+        if !self.topology.book.set_dead_letters(self.entry.addr()) {
+            panic!("the deadletter group is already set");
+        }
+        self
+    }
+
     /// Defines a route to the given destination (local or remote group).
     ///
     /// # Examples
@@ -222,7 +405,7 @@ impl<'t> Local<'t> {
     pub fn route_to<F>(&self, dest: &impl Destination<F>, filter: F) {
         dest.extend_demux(
             self.entry.addr().group_no().expect("invalid addr"),
-            &mut self.demux.borrow_mut(),
+            &self.demux,
             filter,
         );
 
@@ -235,19 +418,19 @@ impl<'t> Local<'t> {
 
     // TODO: deprecate?
     pub fn route_all_to(&self, dest: &Local<'_>) {
-        let addr = dest.entry.addr();
-        self.demux
-            .borrow_mut()
-            .append(move |_, addrs| addrs.push(addr));
+        self.demux.append_local(dest.entry.addr(), |_| true);
     }
 
     /// Mounts a blueprint to this group.
+    ///
+    /// If the system is already running, actors are started once the group
+    /// receives a config. Use `Topology::unmount()` to remove the group.
     pub fn mount(self, blueprint: Blueprint) {
         self.with_group_mut(|group| group.stop_order = blueprint.stop_order);
 
         let addr = self.entry.addr();
         let book = self.topology.book.clone();
-        let ctx = Context::new(book, self.demux.into_inner()).with_group(addr);
+        let ctx = Context::new(book, self.demux).with_group(addr);
         let rt_manager = self.topology.inner.read().rt_manager.clone();
         let object = (blueprint.mount)(ctx, self.name, rt_manager);
         self.entry.insert(object);
@@ -267,7 +450,7 @@ impl<'t> Local<'t> {
 #[sealed]
 pub trait Destination<F> {
     #[doc(hidden)]
-    fn extend_demux(&self, source_group_no: GroupNo, demux: &mut Demux, filter: F);
+    fn extend_demux(&self, source_group_no: GroupNo, demux: &Demux, filter: F);
 
     #[doc(hidden)]
     fn connection_endpoint(&self) -> ConnectionTo;
@@ -278,13 +461,8 @@ impl<F> Destination<F> for Local<'_>
 where
     F: Fn(&Envelope) -> bool + Send + Sync + 'static,
 {
-    fn extend_demux(&self, _: GroupNo, demux: &mut Demux, filter: F) {
-        let addr = self.entry.addr();
-        demux.append(move |envelope, addrs| {
-            if filter(envelope) {
-                addrs.push(addr);
-            }
-        });
+    fn extend_demux(&self, _: GroupNo, demux: &Demux, filter: F) {
+        demux.append_local(self.entry.addr(), filter);
     }
 
     fn connection_endpoint(&self) -> ConnectionTo {
@@ -294,7 +472,6 @@ where
 
 cfg_network!({
     use arc_swap::ArcSwap;
-    use fxhash::FxHashMap;
 
     use crate::{addr::NodeNo, remote::RemoteHandle};
 
@@ -410,7 +587,7 @@ cfg_network!({
     where
         F: Fn(&Envelope, &NodeDiscovery) -> Outcome + Send + Sync + 'static,
     {
-        fn extend_demux(&self, local_group_no: GroupNo, demux: &mut Demux, filter: F) {
+        fn extend_demux(&self, local_group_no: GroupNo, demux: &Demux, filter: F) {
             let nodes = self
                 .topology
                 .inner
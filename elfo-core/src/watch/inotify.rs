@@ -0,0 +1,162 @@
+use std::{
+    ffi::{CString, OsStr, OsString},
+    fs, io, mem,
+    os::{
+        fd::{AsRawFd, FromRawFd, OwnedFd},
+        unix::ffi::OsStrExt,
+    },
+    path::Path,
+    task::{self, Poll},
+};
+
+use tokio::io::unix::AsyncFd;
+
+// Enough for several events with the longest names.
+const BUFFER_SIZE: usize = 4096;
+
+const HEADER_SIZE: usize = mem::size_of::<libc::inotify_event>();
+
+// Watching the whole content, not only modifications, handles atomic writes
+// by renaming and removing files. `IN_MODIFY` isn't watched to emit nothing
+// until the writer closes the file.
+const MASK: u32 = libc::IN_CLOSE_WRITE
+    | libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_ATTRIB
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF
+    | libc::IN_ONLYDIR;
+
+pub(super) enum Change {
+    Modified,
+    /// The watched directory has been removed or moved.
+    Unwatched,
+}
+
+pub(super) struct Inotify {
+    fd: AsyncFd<OwnedFd>,
+    /// The name of the watched file inside the watched directory.
+    /// `None` if the directory itself is watched.
+    name: Option<OsString>,
+    /// Whether the watched file is a symlink. Its target can be replaced by
+    /// replacing another symlink in the directory, e.g. `..data` in mounted
+    /// Kubernetes ConfigMaps, so any new entry is considered a change.
+    is_symlink: bool,
+    buffer: Box<[u8; BUFFER_SIZE]>,
+}
+
+impl Inotify {
+    pub(super) fn new(path: &Path) -> io::Result<Self> {
+        // Files are watched through their parent directories, because many
+        // writers replace files instead of modifying them.
+        let (dir, name) = if path.is_dir() {
+            (path, None)
+        } else {
+            let name = path.file_name().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "the path has no file name")
+            })?;
+            let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+            (dir.unwrap_or(Path::new(".")), Some(name.to_os_string()))
+        };
+
+        let is_symlink = name.is_some()
+            && fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_symlink());
+
+        // SAFETY: the returned descriptor is checked below.
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // SAFETY: the descriptor is valid and owned only here.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let dir = CString::new(dir.as_os_str().as_bytes())?;
+        // SAFETY: both the descriptor and the path are valid.
+        let wd = unsafe { libc::inotify_add_watch(fd.as_raw_fd(), dir.as_ptr(), MASK) };
+        if wd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            fd: AsyncFd::new(fd)?,
+            name,
+            is_symlink,
+            buffer: Box::new([0; BUFFER_SIZE]),
+        })
+    }
+
+    pub(super) fn poll_changed(&mut self, cx: &mut task::Context<'_>) -> Poll<io::Result<Change>> {
+        loop {
+            let mut guard = match self.fd.poll_read_ready(cx) {
+                Poll::Ready(Ok(guard)) => guard,
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            let buffer = &mut self.buffer[..];
+            let len = match guard.try_io(|fd| {
+                // SAFETY: the buffer is valid for writes of its length.
+                let len =
+                    unsafe { libc::read(fd.as_raw_fd(), buffer.as_mut_ptr().cast(), buffer.len()) };
+
+                if len < 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(len as usize)
+                }
+            }) {
+                Ok(Ok(len)) => len,
+                Ok(Err(err)) => return Poll::Ready(Err(err)),
+                // Spurious readiness, wait for the next one.
+                Err(_would_block) => continue,
+            };
+
+            // All events read at once are collapsed into one change.
+            let change = parse(&self.buffer[..len], self.name.as_deref(), self.is_symlink);
+            if let Some(change) = change {
+                return Poll::Ready(Ok(change));
+            }
+        }
+    }
+}
+
+fn parse(mut buffer: &[u8], name: Option<&OsStr>, is_symlink: bool) -> Option<Change> {
+    let mut change = None;
+
+    while buffer.len() >= HEADER_SIZE {
+        // SAFETY: the kernel writes whole events, the header is checked above.
+        // The buffer isn't aligned for the header, so it's read unaligned.
+        let event =
+            unsafe { std::ptr::read_unaligned(buffer.as_ptr().cast::<libc::inotify_event>()) };
+        let end = (HEADER_SIZE + event.len as usize).min(buffer.len());
+        let event_name = trim_nuls(&buffer[HEADER_SIZE..end]);
+        buffer = &buffer[end..];
+
+        if event.mask & libc::IN_IGNORED != 0 {
+            return Some(Change::Unwatched);
+        }
+
+        // Lost events are always relevant, because they can be about the file.
+        // New entries can be on the way to the target of the symlink.
+        let is_relevant = event.mask & libc::IN_Q_OVERFLOW != 0
+            || (is_symlink && event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0)
+            || match name {
+                Some(name) => name.as_bytes() == event_name,
+                None => true,
+            };
+
+        if is_relevant {
+            change = Some(Change::Modified);
+        }
+    }
+
+    change
+}
+
+fn trim_nuls(name: &[u8]) -> &[u8] {
+    let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+    &name[..len]
+}
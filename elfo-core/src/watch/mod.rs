@@ -0,0 +1,214 @@
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use pin_project::pin_project;
+use sealed::sealed;
+
+use crate::{
+    envelope::{Envelope, MessageKind},
+    message::Message,
+    source::{SourceArc, SourceStream, UnattachedSource},
+    tracing::TraceId,
+    Addr,
+};
+
+#[cfg(target_os = "linux")]
+mod inotify;
+mod polling;
+
+/// How often the path is checked if changes aren't watched by the OS.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A source that emits a message once a file or a directory changes.
+/// Clones the message on every change.
+///
+/// On Linux, changes are watched by `inotify`. A file is watched through its
+/// parent directory, so replacing the file (e.g. by renaming another one,
+/// as many editors do) is detected. Changes of files inside a directory are
+/// detected, but not changes of nested directories. If the file is a symlink,
+/// new entries in its directory are also considered changes, so swapping
+/// symlinks (e.g. in mounted Kubernetes ConfigMaps) is detected. If `inotify`
+/// cannot be used or the directory is removed, the source falls back to
+/// polling.
+///
+/// On other systems, the path is polled every second, it's also possible
+/// to force polling by [`Watch::polling()`], e.g. for network filesystems.
+/// Polling compares sizes and modification times, following symlinks,
+/// and reads them in blocking tasks.
+///
+/// One change can produce several messages, e.g. if a file is created and
+/// then written. Use [`Debounce`] to collapse them if it's important.
+///
+/// [`Debounce`]: crate::time::Debounce
+///
+/// # Tracing
+///
+/// Every message starts a new trace, thus a new trace id is generated and
+/// assigned to the current scope.
+///
+/// # Example
+///
+/// ```
+/// # use elfo_core as elfo;
+/// # async fn exec(mut ctx: elfo::Context) {
+/// # use elfo::{message, msg};
+/// use elfo::watch::Watch;
+///
+/// #[message]
+/// struct ReloadFile;
+///
+/// ctx.attach(Watch::new("data/rates.csv", ReloadFile));
+///
+/// while let Some(envelope) = ctx.recv().await {
+///     msg!(match envelope {
+///         ReloadFile => { /* ... */ },
+///     });
+/// }
+/// # }
+/// ```
+pub struct Watch<M> {
+    source: SourceArc<WatchSource<M>>,
+}
+
+#[sealed]
+impl<M: Message> crate::source::SourceHandle for Watch<M> {
+    fn is_terminated(&self) -> bool {
+        self.source.lock().is_none()
+    }
+
+    fn terminate(self) {
+        ward!(self.source.lock()).terminate();
+    }
+}
+
+#[pin_project]
+struct WatchSource<M> {
+    message: M,
+    path: PathBuf,
+    inner: WatchInner,
+}
+
+enum WatchInner {
+    #[cfg(target_os = "linux")]
+    Inotify(inotify::Inotify),
+    Polling(polling::Polling),
+}
+
+impl<M: Message> Watch<M> {
+    /// Creates an unattached instance of [`Watch`], which uses OS
+    /// notifications if possible and polling otherwise.
+    pub fn new(path: impl AsRef<Path>, message: M) -> UnattachedSource<Self> {
+        let path = path.as_ref();
+        Self::from_inner(path, message, WatchInner::new(path))
+    }
+
+    /// Creates an unattached instance of [`Watch`], which polls the path
+    /// with the provided interval.
+    ///
+    /// # Panics
+    ///
+    /// If `interval` is zero.
+    #[track_caller]
+    pub fn polling(
+        path: impl AsRef<Path>,
+        interval: Duration,
+        message: M,
+    ) -> UnattachedSource<Self> {
+        assert!(!interval.is_zero(), "interval must be non-zero");
+
+        let path = path.as_ref();
+        let inner = WatchInner::Polling(polling::Polling::new(path, interval));
+        Self::from_inner(path, message, inner)
+    }
+
+    fn from_inner(path: &Path, message: M, inner: WatchInner) -> UnattachedSource<Self> {
+        let source = WatchSource {
+            message,
+            path: path.to_path_buf(),
+            inner,
+        };
+
+        let source = SourceArc::new(source, false);
+        UnattachedSource::new(source, |source| Self { source })
+    }
+
+    /// Replaces a stored message with the provided one.
+    pub fn set_message(&self, message: M) {
+        let mut guard = ward!(self.source.lock());
+        *guard.stream().project().message = message;
+    }
+}
+
+impl WatchInner {
+    #[cfg(target_os = "linux")]
+    fn new(path: &Path) -> Self {
+        match inotify::Inotify::new(path) {
+            Ok(inotify) => WatchInner::Inotify(inotify),
+            Err(err) => {
+                tracing::warn!(
+                    path = %path.display(),
+                    error = %err,
+                    "failed to watch by inotify, falling back to polling"
+                );
+                WatchInner::Polling(polling::Polling::new(path, DEFAULT_POLL_INTERVAL))
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn new(path: &Path) -> Self {
+        WatchInner::Polling(polling::Polling::new(path, DEFAULT_POLL_INTERVAL))
+    }
+
+    fn poll_changed(&mut self, path: &Path, cx: &mut task::Context<'_>) -> Poll<()> {
+        match self {
+            #[cfg(target_os = "linux")]
+            WatchInner::Inotify(inner) => match inner.poll_changed(cx) {
+                Poll::Ready(Ok(inotify::Change::Modified)) => Poll::Ready(()),
+                // The directory has been removed, so is the watch.
+                // Poll the path to detect when it's created again.
+                Poll::Ready(Ok(inotify::Change::Unwatched)) => {
+                    *self = WatchInner::Polling(polling::Polling::new(path, DEFAULT_POLL_INTERVAL));
+                    Poll::Ready(())
+                }
+                Poll::Ready(Err(err)) => {
+                    tracing::warn!(
+                        path = %path.display(),
+                        error = %err,
+                        "failed to read inotify events, falling back to polling"
+                    );
+                    *self = WatchInner::Polling(polling::Polling::new(path, DEFAULT_POLL_INTERVAL));
+                    self.poll_changed(path, cx)
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            WatchInner::Polling(inner) => inner.poll_changed(cx),
+        }
+    }
+}
+
+impl<M: Message> SourceStream for WatchSource<M> {
+    fn as_any_mut(self: Pin<&mut Self>) -> Pin<&mut dyn Any> {
+        // SAFETY: we only cast here, it cannot move data.
+        unsafe { self.map_unchecked_mut(|s| s) }
+    }
+
+    fn poll_recv(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Envelope>> {
+        let this = self.project();
+
+        if this.inner.poll_changed(this.path, cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        let message = this.message.clone();
+        let kind = MessageKind::Regular { sender: Addr::NULL };
+        let trace_id = TraceId::generate();
+        let envelope = Envelope::with_trace_id(message, kind, trace_id).upcast();
+        Poll::Ready(Some(envelope))
+    }
+}
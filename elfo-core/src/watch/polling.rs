@@ -0,0 +1,110 @@
+use std::{
+    fs,
+    future::Future,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use fxhash::FxHasher;
+use tokio::{
+    task::JoinHandle,
+    time::{Instant, Sleep},
+};
+
+/// Detects changes by comparing fingerprints of the path.
+///
+/// Metadata is read in blocking tasks to not block the actor on slow
+/// filesystems, e.g. network ones.
+pub(super) struct Polling {
+    path: PathBuf,
+    interval: Duration,
+    /// `None` until the first fingerprint is read.
+    /// `Some(None)` if the path doesn't exist.
+    fingerprint: Option<Option<u64>>,
+    /// The fingerprint being read.
+    reading: Option<JoinHandle<Option<u64>>>,
+    sleep: Pin<Box<Sleep>>,
+}
+
+impl Polling {
+    pub(super) fn new(path: &Path, interval: Duration) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            interval,
+            fingerprint: None,
+            reading: Some(spawn_fingerprint(path)),
+            sleep: Box::pin(tokio::time::sleep(interval)),
+        }
+    }
+
+    pub(super) fn poll_changed(&mut self, cx: &mut task::Context<'_>) -> Poll<()> {
+        loop {
+            if let Some(reading) = &mut self.reading {
+                let result = match Pin::new(reading).poll(cx) {
+                    Poll::Ready(result) => result,
+                    Poll::Pending => return Poll::Pending,
+                };
+
+                self.reading = None;
+                self.sleep.as_mut().reset(Instant::now() + self.interval);
+
+                // The task fails only if the runtime is shutting down.
+                if let Ok(fingerprint) = result {
+                    let prev = self.fingerprint.replace(fingerprint);
+                    if matches!(prev, Some(prev) if prev != fingerprint) {
+                        return Poll::Ready(());
+                    }
+                }
+            }
+
+            if self.sleep.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+
+            self.reading = Some(spawn_fingerprint(&self.path));
+        }
+    }
+}
+
+fn spawn_fingerprint(path: &Path) -> JoinHandle<Option<u64>> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || fingerprint(&path))
+}
+
+/// Hashes sizes and modification times of the file or the directory with its
+/// entries. Returns `None` if the path doesn't exist or cannot be read.
+fn fingerprint(path: &Path) -> Option<u64> {
+    let meta = fs::metadata(path).ok()?;
+    let mut hasher = FxHasher::default();
+    hash_metadata(&meta, &mut hasher);
+
+    if meta.is_dir() {
+        let mut entries = fs::read_dir(path)
+            .ok()?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                Some((entry.file_name(), fs::metadata(entry.path()).ok()))
+            })
+            .collect::<Vec<_>>();
+
+        entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        for (name, meta) in &entries {
+            name.hash(&mut hasher);
+            if let Some(meta) = meta {
+                hash_metadata(meta, &mut hasher);
+            }
+        }
+    }
+
+    Some(hasher.finish())
+}
+
+fn hash_metadata(meta: &fs::Metadata, hasher: &mut FxHasher) {
+    meta.is_dir().hash(hasher);
+    meta.len().hash(hasher);
+    meta.modified().ok().hash(hasher);
+}
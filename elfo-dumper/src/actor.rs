@@ -97,8 +97,10 @@ impl Dumper {
         self.ctx
             .attach(Signal::new(SignalKind::UnixHangup, ReopenDumpFile));
 
-        // TODO: use `interval.start_after` to set random time shift.
-        self.interval.start(self.ctx.config().write_interval);
+        // Shift ticks randomly to avoid writing dumps of all classes simultaneously.
+        let write_interval = self.ctx.config().write_interval;
+        self.interval
+            .start_with_jitter(write_interval, write_interval);
 
         while let Some(envelope) = self.ctx.recv().await {
             msg!(match envelope {
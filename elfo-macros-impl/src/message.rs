@@ -18,6 +18,7 @@ struct MessageArgs {
     part: bool,
     transparent: bool,
     dumping_allowed: Option<bool>,
+    high_priority: Option<bool>,
     crate_: Option<Path>,
     not: Vec<String>,
 }
@@ -31,6 +32,7 @@ impl Parse for MessageArgs {
             part: false,
             transparent: false,
             dumping_allowed: None,
+            high_priority: None,
             crate_: None,
             not: Vec::new(),
         };
@@ -44,6 +46,7 @@ impl Parse for MessageArgs {
         // `#[message(elfo = some)]`
         // `#[message(not(Debug))]`
         // `#[message(dumping = "disabled")]`
+        // `#[message(priority = high)]`
         while !input.is_empty() {
             let ident: Ident = input.parse()?;
 
@@ -73,6 +76,16 @@ impl Parse for MessageArgs {
                         return Err(input.error("only `dumping = \"disabled\"` is supported"));
                     }
                 }
+                "priority" => {
+                    let _: Token![=] = input.parse()?;
+                    let priority: Ident = input.parse()?;
+
+                    args.high_priority = Some(match priority.to_string().as_str() {
+                        "high" => true,
+                        "normal" => false,
+                        _ => return Err(input.error("only `high` and `normal` are supported")),
+                    });
+                }
                 // TODO: call it `crate` like in linkme?
                 "elfo" => {
                     let _: Token![=] = input.parse()?;
@@ -112,6 +125,7 @@ impl MessageArgs {
             incompatible(&self.name, "name");
             incompatible(&self.protocol, "protocol");
             incompatible(&self.dumping_allowed, "dumping_allowed");
+            incompatible(&self.high_priority, "priority");
         }
     }
 }
@@ -195,6 +209,7 @@ pub fn message_impl(
 
     // TODO: pass to `_elfo_Wrapper`.
     let dumping_allowed = args.dumping_allowed.unwrap_or(true);
+    let high_priority = args.high_priority.unwrap_or(false);
 
     let network_fns = cfg!(feature = "network").then(|| {
         quote! {
@@ -268,6 +283,7 @@ pub fn message_impl(
                     #internal::metrics::Label::from_static_parts("protocol", #protocol),
                 ],
                 dumping_allowed: #dumping_allowed,
+                high_priority: #high_priority,
                 clone,
                 debug,
                 erase,
@@ -22,6 +22,8 @@ pub fn msg_core(input: TokenStream) -> TokenStream {
 /// * `not(Debug)` — do not derive `Debug`. Useful for custom instances.
 /// * `not(Clone)` — the same for `Clone`.
 /// * `elfo = some::path` — override a path to elfo.
+/// * `priority = high` — deliver the message through the control lane of
+///   mailboxes, i.e. before regular messages.
 #[proc_macro_attribute]
 pub fn message(attr: TokenStream, input: TokenStream) -> TokenStream {
     message_impl(attr, input, parse_quote!(::elfo))
@@ -94,6 +94,8 @@ fn do_encode(
                 Ok(_) => KIND_RESPONSE_OK,
                 Err(RequestError::Failed) => KIND_RESPONSE_FAILED,
                 Err(RequestError::Ignored) => KIND_RESPONSE_IGNORED,
+                // Produced only on the requester's side, never sent.
+                Err(RequestError::TimedOut) => KIND_RESPONSE_FAILED,
             },
             Some(*request_id),
             message.as_ref().ok(),
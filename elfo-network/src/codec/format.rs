@@ -161,6 +161,10 @@ impl NetworkEnvelopePayload {
                 message: Err(RequestError::Ignored),
                 ..
             } => ("", "RequestError::Ignored"),
+            Self::Response {
+                message: Err(RequestError::TimedOut),
+                ..
+            } => ("", "RequestError::TimedOut"),
         }
     }
 }
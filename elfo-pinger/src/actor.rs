@@ -1,11 +1,13 @@
-use std::time::Duration;
-
-use tokio::{select, time};
 use tracing::{debug, info, warn};
 
 use elfo_core::{
-    message, messages::Ping, scope, time::Interval, topology::LocalActorGroup, ActorStatus, Addr,
-    Context, Topology,
+    errors::RequestError,
+    message,
+    messages::{Ping, RequestResolved},
+    msg,
+    time::Interval,
+    topology::LocalActorGroup,
+    ActorStatus, Addr, Context, Topology,
 };
 use elfo_utils::ward;
 
@@ -31,16 +33,14 @@ pub(crate) async fn exec(mut ctx: Context<Config>, topology: Topology) {
 
     interval.start(ctx.config().ping_interval / group_count);
 
-    // Accept envelopes from the mailbox concurrently with pinging
-    // in order to avoid getting stuck with the configurer.
-    // TODO: replace it with async requests in the future.
-    loop {
-        select! {
-            envelope = ctx.recv() => {
-                let envelope = ward!(envelope, break);
-                interval.set_period(ctx.config().ping_interval / group_count);
+    // Pings are sent as async requests in order to avoid getting stuck
+    // with the configurer while a group is pinged.
+    while let Some(envelope) = ctx.recv().await {
+        interval.set_period(ctx.config().ping_interval / group_count);
 
-                if !envelope.is::<PingTick>() || pinging.is_some() {
+        msg!(match envelope {
+            PingTick => {
+                if pinging.is_some() {
                     continue;
                 }
 
@@ -57,14 +57,32 @@ pub(crate) async fn exec(mut ctx: Context<Config>, topology: Topology) {
                 let group = groups.pop().unwrap();
                 let warn_threshold = ctx.config().warn_threshold;
 
-                // Expose a current scope to preserve an original trace id.
-                let fut = scope::expose().within(ping_group(ctx.pruned(), group, warn_threshold));
-                pinging = Some(Box::pin(fut));
-            },
-            responsive = async { pinging.as_mut().unwrap().await }, if pinging.is_some() => {
-                pinging = None;
+                debug!(group = %group.name, "checking a group");
+                let request_id = ctx
+                    .request_to(group.addr, Ping::default())
+                    .all()
+                    .timeout(warn_threshold)
+                    .id()
+                    .await;
+
+                pinging = Some((request_id, group, warn_threshold));
+            }
+            resolved @ RequestResolved => {
+                let (request_id, group, warn_threshold) = ward!(pinging.take(), continue);
+                debug_assert_eq!(resolved.request_id, request_id);
+
+                let responsive = !resolved
+                    .into_responses::<Ping>()
+                    .iter()
+                    .any(|r| matches!(r, Err(RequestError::TimedOut)));
 
                 if !responsive {
+                    warn!(
+                        message = "group hasn't responded in the allowed time",
+                        group = %group.name,
+                        timeout = ?warn_threshold,
+                    );
+
                     timed_out += 1;
 
                     if !is_alarming {
@@ -72,8 +90,9 @@ pub(crate) async fn exec(mut ctx: Context<Config>, topology: Topology) {
                         ctx.set_status(ActorStatus::ALARMING);
                     }
                 }
-            },
-        }
+            }
+            _ => {}
+        });
     }
 }
 
@@ -83,18 +102,3 @@ fn collect_groups(topology: &Topology, exclude: &[Addr]) -> Vec<LocalActorGroup>
         .filter(|group| !exclude.contains(&group.addr))
         .collect()
 }
-
-async fn ping_group(ctx: Context, group: LocalActorGroup, warn_threshold: Duration) -> bool {
-    debug!(group = %group.name, "checking a group");
-    let fut = ctx.request_to(group.addr, Ping::default()).all().resolve();
-    if time::timeout(warn_threshold, fut).await.is_err() {
-        warn!(
-            message = "group hasn't responded in the allowed time",
-            group = %group.name,
-            timeout = ?warn_threshold,
-        );
-        false
-    } else {
-        true
-    }
-}
@@ -0,0 +1,86 @@
+#![cfg(feature = "test-util")]
+
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+use toml::toml;
+
+use elfo::{
+    config::AnyConfig,
+    messages::{ConfigUpdated, UpdateConfig},
+    prelude::*,
+    routers::ConsistentHashRouter,
+};
+
+#[message(ret = usize)]
+struct GetShard(u64);
+
+#[message]
+#[derive(PartialEq)]
+struct Stopped(usize);
+
+#[derive(Debug, Clone, Deserialize)]
+struct Config {
+    shards: usize,
+}
+
+fn blueprint() -> Blueprint {
+    ActorGroup::new()
+        .config::<Config>()
+        .router(ConsistentHashRouter::new(
+            |config: &Config| config.shards,
+            |envelope| {
+                msg!(match envelope {
+                    GetShard(id) => Some(*id),
+                    _ => None,
+                })
+            },
+        ))
+        .exec(|mut ctx: Context<Config, usize>| async move {
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    (GetShard(_), token) => ctx.respond(token, *ctx.key()),
+                    ConfigUpdated => {}
+                });
+            }
+
+            let _ = ctx.send(Stopped(*ctx.key())).await;
+        })
+}
+
+async fn shards(proxy: &elfo::test::Proxy) -> Vec<usize> {
+    let mut shards = Vec::new();
+    for id in 0..100 {
+        shards.push(proxy.request(GetShard(id)).await);
+    }
+    shards
+}
+
+#[tokio::test]
+async fn rebalancing() {
+    let mut proxy = elfo::test::proxy(blueprint(), toml! { shards = 4 }).await;
+
+    let before = shards(&proxy).await;
+    let used = before.iter().copied().collect::<BTreeSet<_>>();
+    assert_eq!(used, (0..4).collect());
+
+    // Unused shards are terminated.
+    let config = AnyConfig::deserialize(toml! { shards = 2 }).unwrap();
+    proxy.send(UpdateConfig::new(config)).await;
+
+    let mut stopped = BTreeSet::new();
+    for _ in 0..2 {
+        msg!(match proxy.recv().await {
+            Stopped(shard) => stopped.insert(shard),
+            envelope => panic!("unexpected message: {envelope:?}"),
+        });
+    }
+    assert_eq!(stopped, (2..4).collect());
+
+    // Only ids of removed shards are moved.
+    let after = shards(&proxy).await;
+    for (before, after) in before.iter().zip(&after) {
+        assert!(*after < 2);
+        assert!(before == after || *before >= 2);
+    }
+}
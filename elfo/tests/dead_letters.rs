@@ -0,0 +1,125 @@
+#![cfg(feature = "test-util")]
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use elfo::{
+    _priv::do_start,
+    messages::DeadLetter,
+    prelude::*,
+    routers::{MapRouter, Outcome},
+    Addr, Local, Topology,
+};
+use elfo_core::config::AnyConfig;
+
+#[message]
+struct Lost(u32);
+
+#[message]
+struct Kept;
+
+#[message(ret = Local<Addr>)]
+struct Whoami;
+
+#[message]
+struct Start;
+
+fn target() -> Blueprint {
+    ActorGroup::new()
+        .router(MapRouter::new(|envelope| {
+            msg!(match envelope {
+                Lost => Outcome::Discard,
+                Kept | Whoami => Outcome::Unicast(0),
+                _ => Outcome::Default,
+            })
+        }))
+        .exec(|mut ctx| async move {
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    Kept => {}
+                    (Whoami, token) => {
+                        ctx.respond(token, ctx.addr().into());
+                        // The actor stops, so its address becomes dead.
+                        break;
+                    }
+                });
+            }
+        })
+}
+
+fn sender() -> Blueprint {
+    ActorGroup::new().exec(|ctx| async move {
+        assert!(ctx.send(Kept).await.is_ok());
+        assert!(ctx.send(Lost(0)).await.is_err());
+
+        let addr = ctx.request(Whoami).resolve().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(ctx.send_to(*addr, Kept).await.is_err());
+
+        // Dead letters are limited per message type.
+        for no in 1..=200 {
+            assert!(ctx.try_send(Lost(no)).is_err());
+        }
+    })
+}
+
+#[tokio::test]
+async fn undeliverable_messages() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let deadletter = ActorGroup::new().exec(move |mut ctx| {
+        let tx = tx.clone();
+
+        async move {
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    letter @ DeadLetter => tx.send(letter).unwrap(),
+                    _ => {}
+                });
+            }
+        }
+    });
+
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    let deadletter_group = topology.local("deadletter").deadletter();
+    let senders = topology.local("senders");
+    let targets = topology.local("targets");
+
+    senders.route_all_to(&targets);
+
+    configurers.mount(elfo_configurer::fixture(&topology, AnyConfig::default()));
+    deadletter_group.mount(deadletter);
+    senders.mount(sender());
+    targets.mount(target());
+
+    do_start(topology, false, |_, _| futures::future::ready(()))
+        .await
+        .expect("cannot start");
+
+    // Discarded by the router.
+    let letter = rx.recv().await.unwrap();
+    let sender = *letter.original_sender;
+    assert!(!sender.is_null());
+    assert!(letter.intended_route.is_none());
+    assert!(matches!(
+        letter.message.downcast_ref::<Lost>(),
+        Some(Lost(0))
+    ));
+
+    // Sent to the dead address.
+    let letter = rx.recv().await.unwrap();
+    assert!(letter.intended_route.is_some());
+    assert!(letter.message.is::<Kept>());
+    assert_eq!(*letter.original_sender, sender);
+
+    let mut count = 0;
+    while tokio::time::timeout(Duration::from_millis(100), rx.recv())
+        .await
+        .is_ok()
+    {
+        count += 1;
+    }
+    assert!((90..=110).contains(&count), "{count}");
+}
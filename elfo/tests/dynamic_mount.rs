@@ -0,0 +1,90 @@
+#![cfg(feature = "test-util")]
+
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use toml::toml;
+
+use elfo::{_priv::do_start, config::AnyConfig, prelude::*, Topology};
+use elfo_configurer::ReloadConfigs;
+
+#[derive(Debug, Clone, Deserialize)]
+struct Config {
+    greeting: String,
+}
+
+#[message]
+struct Hello;
+
+fn tenant(tx: mpsc::UnboundedSender<String>) -> Blueprint {
+    ActorGroup::new().config::<Config>().exec(move |mut ctx| {
+        let tx = tx.clone();
+        async move {
+            tx.send(format!("started: {}", ctx.config().greeting))
+                .unwrap();
+
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    Hello => tx.send(ctx.config().greeting.clone()).unwrap(),
+                    _ => {}
+                });
+            }
+
+            tx.send("stopped".into()).unwrap();
+        }
+    })
+}
+
+#[tokio::test]
+async fn mount_and_unmount() {
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    let configurers_addr = configurers.addr();
+    let config = toml! {
+        [tenant]
+        greeting = "hi"
+    };
+    configurers.mount(elfo_configurer::fixture(&topology, config));
+
+    do_start(topology, false, |ctx, topology| async move {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        for _ in 0..2 {
+            let group = topology.local("tenant");
+            let addr = group.addr();
+            group.mount(tenant(tx.clone()));
+
+            // The group receives its config on reload.
+            let res = ctx.request_to(configurers_addr, ReloadConfigs::default());
+            assert!(res.resolve().await.unwrap().is_ok());
+
+            ctx.send_to(addr, Hello).await.unwrap();
+            assert_eq!(rx.recv().await.unwrap(), "started: hi");
+            assert_eq!(rx.recv().await.unwrap(), "hi");
+
+            topology.unmount("tenant").await;
+            assert_eq!(rx.recv().await.unwrap(), "stopped");
+            assert!(topology.locals().all(|group| group.name != "tenant"));
+            assert!(ctx.send_to(addr, Hello).await.is_err());
+        }
+    })
+    .await
+    .expect("cannot start");
+}
+
+#[tokio::test]
+async fn group_numbers_are_reused() {
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    configurers.mount(elfo_configurer::fixture(&topology, AnyConfig::default()));
+
+    do_start(topology, false, |_ctx, topology| async move {
+        // More cycles than available group numbers.
+        for _ in 0..300 {
+            let group = topology.local("tenant");
+            group.mount(ActorGroup::new().exec(|_ctx| async {}));
+            topology.unmount("tenant").await;
+        }
+    })
+    .await
+    .expect("cannot start");
+}
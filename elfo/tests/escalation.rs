@@ -0,0 +1,90 @@
+#![cfg(feature = "test-util")]
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use toml::toml;
+
+use elfo::{
+    _priv::do_start,
+    messages::{ActorFailed, RestartsExhausted},
+    prelude::*,
+    RestartParams, RestartPolicy, Topology,
+};
+
+#[tokio::test]
+async fn failures_are_escalated() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let failing = ActorGroup::new()
+        .restart_policy(RestartPolicy::on_failure(
+            RestartParams::new(Duration::ZERO, Duration::ZERO)
+                .auto_reset(Duration::MAX)
+                .max_retries(std::num::NonZeroU64::new(2)),
+        ))
+        .exec(|ctx| async move {
+            if ctx.start_info().cause.is_restarted() {
+                panic!("oops");
+            }
+            anyhow::bail!(anyhow::anyhow!("inner").context("outer"))
+        });
+
+    let alerting = ActorGroup::new().exec(move |mut ctx| {
+        let tx = tx.clone();
+
+        async move {
+            while let Some(envelope) = ctx.recv().await {
+                let event = msg!(match envelope {
+                    failed @ ActorFailed => format!(
+                        "{}: {:?}, {:?}",
+                        failed.meta.group, failed.error_chain, failed.restart_in
+                    ),
+                    exhausted @ RestartsExhausted => format!(
+                        "{}: exhausted {}",
+                        exhausted.meta.group, exhausted.max_retries
+                    ),
+                    _ => continue,
+                });
+                tx.send(event).unwrap();
+
+                // Let other escalations fill the mailbox.
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        }
+    });
+
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    let alerting_group = topology.local("alerting").escalation();
+    let failing_group = topology.local("failing");
+
+    // Escalations aren't dropped even if the mailbox is full.
+    configurers.mount(elfo_configurer::fixture(
+        &topology,
+        toml! {
+            [alerting.system.mailbox]
+            capacity = 1
+        },
+    ));
+    alerting_group.mount(alerting);
+    failing_group.mount(failing);
+
+    do_start(topology, false, |_, _| futures::future::ready(()))
+        .await
+        .expect("cannot start");
+
+    let mut events = Vec::new();
+    for _ in 0..4 {
+        events.push(rx.recv().await.unwrap());
+    }
+
+    assert_eq!(
+        events,
+        [
+            r#"failing: ["outer", "inner"], Some(0ns)"#,
+            r#"failing: ["panic: oops"], Some(0ns)"#,
+            r#"failing: ["panic: oops"], None"#,
+            "failing: exhausted 2",
+        ]
+    );
+}
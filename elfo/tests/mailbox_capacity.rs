@@ -0,0 +1,119 @@
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::sync::Notify;
+use toml::toml;
+
+use elfo::{
+    config::AnyConfig,
+    errors::TrySendError,
+    messages::{ConfigUpdated, UpdateConfig},
+    prelude::*,
+};
+
+#[message(ret = ())]
+struct Block;
+
+#[message]
+struct Sample;
+
+fn blueprint(capacity: usize, unblock: Arc<Notify>) -> Blueprint {
+    ActorGroup::new()
+        .mailbox_capacity(capacity)
+        .exec(move |mut ctx| {
+            let unblock = unblock.clone();
+            async move {
+                while let Some(envelope) = ctx.recv().await {
+                    msg!(match envelope {
+                        (Block, token) => {
+                            ctx.respond(token, ());
+                            unblock.notified().await;
+                        }
+                        Sample | ConfigUpdated => {}
+                        _ => unreachable!(),
+                    });
+                }
+            }
+        })
+}
+
+fn fill(proxy: &elfo::test::Proxy) -> usize {
+    let mut count = 0;
+    loop {
+        match proxy.try_send(Sample) {
+            Ok(()) => count += 1,
+            Err(TrySendError::Full(_)) => return count,
+            Err(TrySendError::Closed(_)) => panic!("closed"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn capacity_from_blueprint() {
+    let unblock = Arc::new(Notify::new());
+    let proxy = elfo::test::proxy(blueprint(3, unblock.clone()), AnyConfig::default()).await;
+
+    proxy.request(Block).await;
+    assert_eq!(fill(&proxy), 3);
+
+    unblock.notify_one();
+    proxy.request(Block).await;
+    assert_eq!(fill(&proxy), 3);
+    unblock.notify_one();
+}
+
+#[tokio::test]
+async fn capacity_from_config() {
+    let unblock = Arc::new(Notify::new());
+    let config = toml! {
+        [system.mailbox]
+        capacity = 2
+    };
+    let proxy = elfo::test::proxy(blueprint(5, unblock.clone()), config).await;
+
+    proxy.request(Block).await;
+    assert_eq!(fill(&proxy), 2);
+
+    // The new capacity is applied to the running actor.
+    let config = AnyConfig::deserialize(toml! {
+        [system.mailbox]
+        capacity = 4
+    })
+    .unwrap();
+    proxy.send(UpdateConfig::new(config)).await;
+    // `UpdateConfig` is delivered through the control lane and doesn't occupy a slot.
+    assert_eq!(fill(&proxy), 2);
+
+    // Without the override, the blueprint's capacity is used.
+    unblock.notify_one();
+    proxy.request(Block).await;
+    proxy.send(UpdateConfig::new(AnyConfig::default())).await;
+    assert_eq!(fill(&proxy), 5);
+    unblock.notify_one();
+}
+
+#[tokio::test]
+async fn zero_capacity_from_config() {
+    let unblock = Arc::new(Notify::new());
+    let proxy = elfo::test::proxy(blueprint(2, unblock.clone()), AnyConfig::default()).await;
+
+    let config = AnyConfig::deserialize(toml! {
+        [system.mailbox]
+        capacity = 0
+    })
+    .unwrap();
+    assert!(proxy.request(UpdateConfig::new(config)).await.is_err());
+
+    // The previous capacity is kept.
+    proxy.request(Block).await;
+    assert_eq!(fill(&proxy), 2);
+    unblock.notify_one();
+}
+
+#[test]
+#[should_panic(expected = "mailbox capacity must be non-zero")]
+fn zero_capacity_from_blueprint() {
+    let _ = ActorGroup::new().mailbox_capacity(0);
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "test-util")]
+
+use std::{sync::Arc, time::Duration};
+
+use serde::Deserialize;
+use tokio::sync::Notify;
+use toml::toml;
+
+use elfo::{
+    _priv::do_start,
+    config::AnyConfig,
+    errors::RequestError,
+    messages::{ConfigUpdated, UpdateConfig},
+    prelude::*,
+    Topology,
+};
+
+#[message(ret = ())]
+struct Block;
+
+#[message(ret = ())]
+struct Ask;
+
+#[message]
+#[derive(PartialEq)]
+struct Sample(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Received(u32);
+
+#[tokio::test]
+async fn stale_envelopes_are_dropped() {
+    let unblock = Arc::new(Notify::new());
+    let unblock1 = unblock.clone();
+
+    let blueprint = ActorGroup::new().exec(move |mut ctx| {
+        let unblock = unblock1.clone();
+        async move {
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    (Block, token) => {
+                        ctx.respond(token, ());
+                        unblock.notified().await;
+                    }
+                    Sample(no) => ctx.send(Received(no)).await.unwrap(),
+                    ConfigUpdated => ctx.send(Received(0)).await.unwrap(),
+                    _ => unreachable!(),
+                });
+            }
+        }
+    });
+
+    let config = toml! {
+        [system.mailbox]
+        max_latency = "50ms"
+    };
+    let mut proxy = elfo::test::proxy(blueprint, config.clone()).await;
+
+    proxy.request(Block).await;
+    proxy.send(Sample(1)).await;
+    // System messages are never dropped.
+    let config = AnyConfig::deserialize(config).unwrap();
+    proxy.send(UpdateConfig::new(config)).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    proxy.send(Sample(2)).await;
+    unblock.notify_one();
+
+    assert_msg_eq!(proxy.recv().await, Received(0));
+    assert_msg_eq!(proxy.recv().await, Received(2));
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+}
+
+#[tokio::test]
+async fn stale_requests_are_failed() {
+    let unblock = Arc::new(Notify::new());
+    let unblock1 = unblock.clone();
+
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    let tester = topology.local("tester");
+    let tester_addr = tester.addr();
+
+    configurers.mount(elfo_configurer::fixture(
+        &topology,
+        toml! {
+            [tester.system.mailbox]
+            max_latency = "50ms"
+        },
+    ));
+    tester.mount(ActorGroup::new().exec(move |mut ctx| {
+        let unblock = unblock1.clone();
+        async move {
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    (Block, token) => {
+                        ctx.respond(token, ());
+                        unblock.notified().await;
+                    }
+                    (Ask, token) => {
+                        drop(token);
+                        panic!("stale requests must be dropped");
+                    }
+                    _ => {}
+                });
+            }
+        }
+    }));
+
+    do_start(topology, false, |ctx, _| async move {
+        ctx.request_to(tester_addr, Block).resolve().await.unwrap();
+
+        let ask = ctx.request_to(tester_addr, Ask).resolve();
+        let unblock = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            unblock.notify_one();
+        };
+        let (response, _) = tokio::join!(ask, unblock);
+        assert!(matches!(response, Err(RequestError::Failed)));
+    })
+    .await
+    .expect("cannot start");
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use tokio::sync::Notify;
+use toml::toml;
+
+use elfo::{config::AnyConfig, prelude::*, MailboxPolicy};
+
+#[message(ret = ())]
+struct Block;
+
+#[message]
+#[derive(PartialEq)]
+struct Sample(u32);
+
+fn blueprint(policy: MailboxPolicy, unblock: Arc<Notify>) -> Blueprint {
+    ActorGroup::new()
+        .mailbox_capacity(2)
+        .mailbox_policy(policy)
+        .exec(move |mut ctx| {
+            let unblock = unblock.clone();
+            async move {
+                while let Some(envelope) = ctx.recv().await {
+                    msg!(match envelope {
+                        (Block, token) => {
+                            ctx.respond(token, ());
+                            unblock.notified().await;
+                        }
+                        msg @ Sample => ctx.send(msg).await.unwrap(),
+                        _ => unreachable!(),
+                    });
+                }
+            }
+        })
+}
+
+#[tokio::test]
+async fn drop_oldest() {
+    let unblock = Arc::new(Notify::new());
+    let blueprint = blueprint(MailboxPolicy::drop_oldest(), unblock.clone());
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    proxy.request(Block).await;
+    for no in 1..=4 {
+        // Neither `send()` nor `try_send()` waits.
+        proxy.send(Sample(no)).await;
+    }
+    unblock.notify_one();
+
+    assert_msg_eq!(proxy.recv().await, Sample(3));
+    assert_msg_eq!(proxy.recv().await, Sample(4));
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+}
+
+#[tokio::test]
+async fn policy_from_config() {
+    let unblock = Arc::new(Notify::new());
+    let blueprint = blueprint(MailboxPolicy::block(), unblock.clone());
+    let config = toml! {
+        [system.mailbox]
+        policy = "DropNewest"
+    };
+    let mut proxy = elfo::test::proxy(blueprint, config).await;
+
+    proxy.request(Block).await;
+    for no in 1..=4 {
+        assert!(proxy.try_send(Sample(no)).is_ok());
+    }
+    unblock.notify_one();
+
+    assert_msg_eq!(proxy.recv().await, Sample(1));
+    assert_msg_eq!(proxy.recv().await, Sample(2));
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+}
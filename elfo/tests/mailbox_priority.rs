@@ -0,0 +1,63 @@
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use tokio::sync::Notify;
+
+use elfo::{config::AnyConfig, messages::Terminate, prelude::*, TerminationPolicy};
+
+#[message(ret = ())]
+struct Block;
+
+#[message]
+#[derive(PartialEq)]
+struct Sample(u32);
+
+#[message(priority = high)]
+#[derive(PartialEq)]
+struct Urgent(u32);
+
+// Reported through the data lane to keep the order.
+#[message]
+#[derive(PartialEq)]
+struct Handled(u32);
+
+#[tokio::test]
+async fn control_lane_is_drained_first() {
+    let unblock = Arc::new(Notify::new());
+    let unblock1 = unblock.clone();
+
+    let blueprint = ActorGroup::new()
+        .termination_policy(TerminationPolicy::manually())
+        .exec(move |mut ctx| {
+            let unblock = unblock1.clone();
+            async move {
+                while let Some(envelope) = ctx.recv().await {
+                    msg!(match envelope {
+                        (Block, token) => {
+                            ctx.respond(token, ());
+                            unblock.notified().await;
+                        }
+                        Sample(no) => ctx.send(Handled(no)).await.unwrap(),
+                        Urgent(no) => ctx.send(Handled(no)).await.unwrap(),
+                        Terminate => ctx.send(Handled(0)).await.unwrap(),
+                        _ => unreachable!(),
+                    });
+                }
+            }
+        });
+
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    proxy.request(Block).await;
+    proxy.send(Sample(1)).await;
+    proxy.send(Sample(2)).await;
+    proxy.send(Urgent(3)).await;
+    proxy.send(Terminate::default()).await;
+    proxy.send(Urgent(4)).await;
+    unblock.notify_one();
+
+    for no in [3, 0, 4, 1, 2] {
+        assert_msg_eq!(proxy.recv().await, Handled(no));
+    }
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "test-util")]
+
+use elfo::{
+    config::AnyConfig,
+    messages::Down,
+    prelude::*,
+    routers::{MapRouter, Outcome},
+    ActorStatusKind, Addr, Local,
+};
+
+#[message(ret = Local<Addr>)]
+struct GetAddr(u32);
+
+#[message]
+struct Monitor(u32, Local<Addr>);
+
+#[message]
+struct Demonitor(u32, Local<Addr>);
+
+#[message]
+struct Link(u32, Local<Addr>);
+
+#[message]
+struct Unlink(u32, Local<Addr>);
+
+#[message]
+struct Stop(u32);
+
+#[message]
+struct Fail(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Downed {
+    key: u32,
+    addr: Local<Addr>,
+    status: ActorStatusKind,
+    details: Option<String>,
+}
+
+fn blueprint() -> Blueprint {
+    ActorGroup::new()
+        .router(MapRouter::new(|envelope| {
+            msg!(match envelope {
+                GetAddr(key)
+                | Monitor(key, _)
+                | Demonitor(key, _)
+                | Link(key, _)
+                | Unlink(key, _)
+                | Stop(key)
+                | Fail(key) => Outcome::Unicast(*key),
+                _ => Outcome::Default,
+            })
+        }))
+        .exec(|mut ctx| async move {
+            let key = *ctx.key();
+
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    (GetAddr, token) => ctx.respond(token, ctx.addr().into()),
+                    Monitor(_, addr) => ctx.monitor(*addr),
+                    Demonitor(_, addr) => ctx.demonitor(*addr),
+                    Link(_, addr) => ctx.link(*addr),
+                    Unlink(_, addr) => ctx.unlink(*addr),
+                    Stop => break,
+                    Fail => anyhow::bail!("oops"),
+                    Down { addr, status, .. } => {
+                        let downed = Downed {
+                            key,
+                            addr,
+                            status: status.kind(),
+                            details: status.details().map(Into::into),
+                        };
+                        ctx.send(downed).await.unwrap();
+                    }
+                });
+            }
+
+            Ok(())
+        })
+}
+
+async fn addr(proxy: &elfo::test::Proxy, key: u32) -> Local<Addr> {
+    proxy.request(GetAddr(key)).await
+}
+
+#[tokio::test]
+async fn monitor() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    let addr2 = addr(&proxy, 2).await;
+    let addr3 = addr(&proxy, 3).await;
+    proxy.send(Monitor(1, addr2)).await;
+    proxy.send(Monitor(1, addr3)).await;
+
+    proxy.send(Stop(2)).await;
+    assert_msg_eq!(
+        proxy.recv().await,
+        Downed {
+            key: 1,
+            addr: addr2,
+            status: ActorStatusKind::Terminated,
+            details: None,
+        }
+    );
+
+    proxy.send(Fail(3)).await;
+    assert_msg_eq!(
+        proxy.recv().await,
+        Downed {
+            key: 1,
+            addr: addr3,
+            status: ActorStatusKind::Failed,
+            details: Some("oops".into()),
+        }
+    );
+
+    // Finished actors are reported immediately.
+    proxy.send(Monitor(1, addr3)).await;
+    assert_msg!(proxy.recv().await, Downed { key: 1, .. });
+}
+
+#[tokio::test]
+async fn link() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    let addr2 = addr(&proxy, 2).await;
+    let addr3 = addr(&proxy, 3).await;
+    proxy.send(Monitor(1, addr2)).await;
+    proxy.send(Monitor(1, addr3)).await;
+    proxy.send(Link(2, addr3)).await;
+
+    // Normal termination doesn't affect linked actors.
+    let addr4 = addr(&proxy, 4).await;
+    proxy.send(Link(2, addr4)).await;
+    proxy.send(Stop(4)).await;
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+    assert_eq!(*addr(&proxy, 2).await, *addr2);
+
+    // Linked actors fail together.
+    proxy.send(Fail(3)).await;
+    let mut downed = [proxy.recv().await, proxy.recv().await].map(|envelope| {
+        msg!(match envelope {
+            downed @ Downed => downed,
+            _ => unreachable!(),
+        })
+    });
+    downed.sort_by_key(|downed| downed.details.clone());
+
+    assert_eq!(*downed[0].addr, *addr2);
+    assert_eq!(downed[0].status, ActorStatusKind::Failed);
+    assert_eq!(
+        downed[0].details.as_deref(),
+        Some(&*format!("linked actor {} failed", *addr3))
+    );
+    assert_eq!(*downed[1].addr, *addr3);
+    assert_eq!(downed[1].details.as_deref(), Some("oops"));
+}
+
+#[tokio::test]
+async fn demonitor_and_unlink() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    let addr2 = addr(&proxy, 2).await;
+    let addr3 = addr(&proxy, 3).await;
+    proxy.send(Monitor(1, addr2)).await;
+    proxy.send(Demonitor(1, addr2)).await;
+    proxy.send(Monitor(1, addr3)).await;
+
+    // Unlinked actors don't fail together.
+    proxy.send(Link(2, addr3)).await;
+    proxy.send(Unlink(3, addr2)).await;
+    proxy.send(Fail(3)).await;
+    assert_msg_eq!(
+        proxy.recv().await,
+        Downed {
+            key: 1,
+            addr: addr3,
+            status: ActorStatusKind::Failed,
+            details: Some("oops".into()),
+        }
+    );
+    assert_eq!(*addr(&proxy, 2).await, *addr2);
+
+    // Demonitored actors aren't reported.
+    proxy.send(Stop(2)).await;
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+}
@@ -0,0 +1,132 @@
+#![cfg(feature = "test-util")]
+
+use std::time::Duration;
+
+use elfo::{
+    config::AnyConfig,
+    prelude::*,
+    routers::{MapRouter, Outcome},
+    RestartParams, RestartPolicy,
+};
+
+#[message]
+struct Touch(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Started {
+    key: u32,
+    reactivated: bool,
+}
+
+#[message]
+#[derive(PartialEq)]
+struct Stopped(u32);
+
+#[tokio::test(start_paused = true)]
+async fn idle_actors_are_passivated() {
+    let blueprint = ActorGroup::new()
+        .router(MapRouter::new(|envelope| {
+            msg!(match envelope {
+                Touch(key) => Outcome::Unicast(*key),
+                _ => Outcome::Default,
+            })
+        }))
+        .restart_policy(RestartPolicy::always(RestartParams::new(
+            Duration::from_secs(1),
+            Duration::from_secs(1),
+        )))
+        .idle_timeout(Duration::from_secs(10))
+        .exec(|mut ctx| async move {
+            let key = *ctx.key();
+            let reactivated = ctx.start_info().cause.is_reactivated();
+            ctx.send(Started { key, reactivated }).await.unwrap();
+
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    Touch => {}
+                });
+            }
+
+            ctx.send(Stopped(key)).await.unwrap();
+        });
+
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    proxy.send(Touch(1)).await;
+    let started = Started {
+        key: 1,
+        reactivated: false,
+    };
+    assert_msg_eq!(proxy.recv().await, started);
+
+    // Messages reset the timer.
+    for _ in 0..3 {
+        tokio::time::sleep(Duration::from_secs(6)).await;
+        proxy.send(Touch(1)).await;
+        proxy.sync().await;
+        assert!(proxy.try_recv().await.is_none());
+    }
+
+    // The idle actor is passivated and isn't restarted.
+    tokio::time::sleep(Duration::from_secs(11)).await;
+    assert_msg_eq!(proxy.recv().await, Stopped(1));
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+
+    // The next message starts the actor again.
+    proxy.send(Touch(1)).await;
+    let started = Started {
+        key: 1,
+        reactivated: true,
+    };
+    assert_msg_eq!(proxy.recv().await, started);
+}
+
+#[tokio::test(start_paused = true)]
+async fn messages_during_passivation_start_new_actor() {
+    let blueprint = ActorGroup::new()
+        .router(MapRouter::new(|envelope| {
+            msg!(match envelope {
+                Touch(key) => Outcome::Unicast(*key),
+                _ => Outcome::Default,
+            })
+        }))
+        .idle_timeout(Duration::from_secs(10))
+        .exec(|mut ctx| async move {
+            let key = *ctx.key();
+            let reactivated = ctx.start_info().cause.is_reactivated();
+            ctx.send(Started { key, reactivated }).await.unwrap();
+
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    Touch => {}
+                });
+            }
+
+            // The actor is still alive, but closed.
+            ctx.send(Stopped(key)).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    proxy.send(Touch(1)).await;
+    let started = Started {
+        key: 1,
+        reactivated: false,
+    };
+    assert_msg_eq!(proxy.recv().await, started);
+
+    tokio::time::sleep(Duration::from_secs(11)).await;
+    assert_msg_eq!(proxy.recv().await, Stopped(1));
+
+    // The message isn't lost, but starts the actor again.
+    proxy.send(Touch(1)).await;
+    let started = Started {
+        key: 1,
+        reactivated: true,
+    };
+    assert_msg_eq!(proxy.recv().await, started);
+}
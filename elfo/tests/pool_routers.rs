@@ -0,0 +1,116 @@
+#![cfg(feature = "test-util")]
+
+use std::sync::Arc;
+
+use serde::Deserialize;
+use tokio::sync::Notify;
+use toml::toml;
+
+use elfo::{
+    config::AnyConfig,
+    messages::{ConfigUpdated, UpdateConfig},
+    prelude::*,
+    routers::{LeastLoadedRouter, RoundRobinRouter, Router},
+    Envelope,
+};
+
+#[message(ret = usize)]
+struct GetKey;
+
+#[message(ret = ())]
+struct Block;
+
+#[message]
+struct Job;
+
+#[message]
+#[derive(PartialEq)]
+struct Done(usize);
+
+#[message]
+#[derive(PartialEq)]
+struct Stopped(usize);
+
+#[derive(Debug, Clone, Deserialize)]
+struct Config {
+    workers: usize,
+}
+
+fn is_pooled(envelope: &Envelope) -> bool {
+    msg!(match envelope {
+        GetKey | Block | Job => true,
+        _ => false,
+    })
+}
+
+fn blueprint(router: impl Router<Config, Key = usize>, unblock: Arc<Notify>) -> Blueprint {
+    ActorGroup::new()
+        .config::<Config>()
+        .router(router)
+        .exec(move |mut ctx| {
+            let unblock = unblock.clone();
+            async move {
+                let key = *ctx.key();
+
+                while let Some(envelope) = ctx.recv().await {
+                    msg!(match envelope {
+                        (GetKey, token) => ctx.respond(token, key),
+                        (Block, token) => {
+                            ctx.respond(token, ());
+                            unblock.notified().await;
+                        }
+                        Job => ctx.send(Done(key)).await.unwrap(),
+                        ConfigUpdated => {}
+                    });
+                }
+
+                let _ = ctx.send(Stopped(key)).await;
+            }
+        })
+}
+
+#[tokio::test]
+async fn round_robin() {
+    let router = RoundRobinRouter::new(|config: &Config| config.workers, is_pooled);
+    let unblock = Arc::new(Notify::new());
+    let mut proxy = elfo::test::proxy(blueprint(router, unblock), toml! { workers = 3 }).await;
+
+    let mut keys = Vec::new();
+    for _ in 0..6 {
+        keys.push(proxy.request(GetKey).await);
+    }
+    assert_eq!(keys, [0, 1, 2, 0, 1, 2]);
+
+    // Actors out of the pool are terminated.
+    let config = AnyConfig::deserialize(toml! { workers = 2 }).unwrap();
+    proxy.send(UpdateConfig::new(config)).await;
+    assert_msg_eq!(proxy.recv().await, Stopped(2));
+
+    let mut keys = Vec::new();
+    for _ in 0..4 {
+        keys.push(proxy.request(GetKey).await);
+    }
+    assert_eq!(keys, [0, 1, 0, 1]);
+}
+
+#[tokio::test]
+async fn least_loaded() {
+    let router = LeastLoadedRouter::new(|config: &Config| config.workers, is_pooled);
+    let unblock = Arc::new(Notify::new());
+    let config = toml! { workers = 2 };
+    let mut proxy = elfo::test::proxy(blueprint(router, unblock.clone()), config).await;
+
+    // All mailboxes are empty, so ties are broken in turn.
+    proxy.request(Block).await;
+    proxy.send(Job).await;
+    assert_msg_eq!(proxy.recv().await, Done(1));
+    proxy.send(Job).await;
+
+    // The first actor is blocked and has an envelope in the mailbox.
+    for _ in 0..4 {
+        assert_eq!(proxy.request(GetKey).await, 1);
+    }
+
+    unblock.notify_one();
+    assert_msg_eq!(proxy.recv().await, Done(0));
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "test-util")]
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use metrics::{GaugeValue, Key, Recorder, Unit};
+
+use elfo::{config::AnyConfig, prelude::*};
+
+#[message]
+struct Connected;
+
+#[message]
+#[derive(PartialEq)]
+struct Sample(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Handled(u32);
+
+#[message]
+struct TimedOut;
+
+#[tokio::test]
+async fn skipped_envelopes_are_kept() {
+    let blueprint = ActorGroup::new().exec(|mut ctx| async move {
+        ctx.recv_matching(|e| e.is::<Connected>()).await.unwrap();
+        ctx.send(Handled(0)).await.unwrap();
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Sample(no) => ctx.send(Handled(no)).await.unwrap(),
+            });
+        }
+    });
+
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    proxy.send(Sample(1)).await;
+    proxy.send(Sample(2)).await;
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+
+    proxy.send(Connected).await;
+    proxy.send(Sample(3)).await;
+
+    for no in 0..=3 {
+        assert_msg_eq!(proxy.recv().await, Handled(no));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn timeout() {
+    let blueprint = ActorGroup::new().exec(|mut ctx| async move {
+        let timeout = Duration::from_secs(1);
+        let err = ctx
+            .recv_matching_timeout(timeout, |e| e.is::<Connected>())
+            .await
+            .unwrap_err();
+        assert!(err.is_timeout());
+        ctx.send(TimedOut).await.unwrap();
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Sample(no) => ctx.send(Handled(no)).await.unwrap(),
+            });
+        }
+    });
+
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    proxy.send(Sample(1)).await;
+    assert_msg!(proxy.recv().await, TimedOut);
+    assert_msg_eq!(proxy.recv().await, Handled(1));
+}
+
+#[message]
+struct Close;
+
+#[message]
+struct Finished;
+
+#[tokio::test]
+async fn skipped_envelopes_after_closing() {
+    let blueprint = ActorGroup::new().exec(|mut ctx| async move {
+        ctx.recv_matching(|e| e.is::<Close>()).await.unwrap();
+        assert!(ctx.close());
+
+        // Skipped envelopes are returned before `None`.
+        assert!(ctx.recv_matching(|e| e.is::<Connected>()).await.is_none());
+        let envelope = ctx.recv_matching(|e| e.is::<Sample>()).await.unwrap();
+        assert_msg_eq!(envelope, Sample(1));
+        assert!(ctx.recv_matching(|e| e.is::<Connected>()).await.is_none());
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Sample(no) => ctx.send(Handled(no)).await.unwrap(),
+            });
+        }
+
+        ctx.send(Finished).await.unwrap();
+    });
+
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    proxy.send(Sample(1)).await;
+    proxy.send(Sample(2)).await;
+    proxy.send(Close).await;
+
+    assert_msg_eq!(proxy.recv().await, Handled(2));
+    assert_msg!(proxy.recv().await, Finished);
+}
+
+// === Accounting ===
+
+#[message]
+struct Deferred(u32);
+
+/// Counts `elfo_message_handling_time_seconds` records per message.
+#[derive(Default)]
+struct HandlingTimes(Mutex<HashMap<String, usize>>);
+
+impl HandlingTimes {
+    fn get(&self, message: &str) -> usize {
+        self.0
+            .lock()
+            .unwrap()
+            .get(message)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl Recorder for HandlingTimes {
+    fn register_counter(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+    fn register_gauge(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+    fn register_histogram(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+    fn increment_counter(&self, _: &Key, _: u64) {}
+    fn update_gauge(&self, _: &Key, _: GaugeValue) {}
+
+    fn record_histogram(&self, key: &Key, _: f64) {
+        if key.name() != "elfo_message_handling_time_seconds" {
+            return;
+        }
+
+        let mut counts = self.0.lock().unwrap();
+        for label in key.labels().filter(|label| label.key() == "message") {
+            *counts.entry(label.value().to_string()).or_default() += 1;
+        }
+    }
+}
+
+fn handling_times() -> &'static HandlingTimes {
+    static RECORDER: OnceLock<&'static HandlingTimes> = OnceLock::new();
+    RECORDER.get_or_init(|| {
+        let recorder: &HandlingTimes = Box::leak(Box::default());
+        metrics::set_recorder(recorder).unwrap();
+        recorder
+    })
+}
+
+#[tokio::test]
+async fn skipped_envelopes_are_accounted_once() {
+    let handling_times = handling_times();
+
+    let blueprint = ActorGroup::new().exec(|mut ctx| async move {
+        ctx.recv_matching(|e| e.is::<Connected>()).await.unwrap();
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Deferred(no) => ctx.send(Handled(no)).await.unwrap(),
+            });
+        }
+    });
+
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    proxy.send(Deferred(1)).await;
+    proxy.send(Deferred(2)).await;
+    proxy.send(Connected).await;
+
+    assert_msg_eq!(proxy.recv().await, Handled(1));
+    assert_msg_eq!(proxy.recv().await, Handled(2));
+    // The handling time is emitted on the next `recv()`.
+    proxy.sync().await;
+    assert_eq!(handling_times.get("Deferred"), 2);
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "test-util")]
+
+use std::{collections::HashMap, time::Duration};
+
+use elfo::{
+    config::AnyConfig, errors::RequestError, messages::RequestResolved, prelude::*,
+    test::extract_request,
+};
+
+#[message(ret = u32)]
+struct Query(u32);
+
+#[message]
+struct Start(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Answered(u32, Result<u32, String>);
+
+#[message]
+#[derive(PartialEq)]
+struct Echo(u32);
+
+fn blueprint() -> Blueprint {
+    ActorGroup::new().exec(|mut ctx| async move {
+        let mut in_flight = HashMap::new();
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Start(no) => {
+                    let timeout = Duration::from_secs(no.into());
+                    let request_id = ctx.request(Query(no)).timeout(timeout).id().await;
+                    in_flight.insert(request_id, no);
+                }
+                resolved @ RequestResolved => {
+                    let no = in_flight.remove(&resolved.request_id).unwrap();
+                    let response = resolved.into_response::<Query>();
+                    let response = response.map_err(|err| err.to_string());
+                    ctx.send(Answered(no, response)).await.unwrap();
+                }
+                msg @ Echo => ctx.send(msg).await.unwrap(),
+            });
+        }
+    })
+}
+
+#[tokio::test(start_paused = true)]
+async fn responses_are_delivered_to_mailbox() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    proxy.send(Start(1)).await;
+    proxy.send(Start(2)).await;
+    let (_, token1) = extract_request::<Query>(proxy.recv().await);
+    let (_, token2) = extract_request::<Query>(proxy.recv().await);
+
+    // The actor isn't blocked by requests in flight.
+    proxy.send(Echo(0)).await;
+    assert_msg_eq!(proxy.recv().await, Echo(0));
+
+    proxy.respond(token2, 20);
+    assert_msg_eq!(proxy.recv().await, Answered(2, Ok(20)));
+    proxy.respond(token1, 10);
+    assert_msg_eq!(proxy.recv().await, Answered(1, Ok(10)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn timeout() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    proxy.send(Start(1)).await;
+    let (_, token) = extract_request::<Query>(proxy.recv().await);
+
+    let expected = Err(RequestError::TimedOut.to_string());
+    assert_msg_eq!(proxy.recv().await, Answered(1, expected));
+
+    // The late response is dropped.
+    proxy.respond(token, 10);
+    proxy.send(Echo(0)).await;
+    assert_msg_eq!(proxy.recv().await, Echo(0));
+}
+
+#[tokio::test(start_paused = true)]
+async fn timeouts_are_ordered() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    proxy.send(Start(3)).await;
+    let (_, _token3) = extract_request::<Query>(proxy.recv().await);
+    proxy.send(Start(1)).await;
+    let (_, _token1) = extract_request::<Query>(proxy.recv().await);
+    proxy.send(Start(2)).await;
+    let (_, token2) = extract_request::<Query>(proxy.recv().await);
+
+    // A resolved request isn't timed out.
+    proxy.respond(token2, 20);
+    assert_msg_eq!(proxy.recv().await, Answered(2, Ok(20)));
+
+    let expected = Err(RequestError::TimedOut.to_string());
+    assert_msg_eq!(proxy.recv().await, Answered(1, expected.clone()));
+    assert_msg_eq!(proxy.recv().await, Answered(3, expected));
+}
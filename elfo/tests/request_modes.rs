@@ -0,0 +1,159 @@
+#![cfg(feature = "test-util")]
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+    time::Duration,
+};
+
+use elfo::{
+    _priv::do_start,
+    prelude::*,
+    routers::{MapRouter, Outcome},
+    Topology,
+};
+use elfo_core::config::AnyConfig;
+
+#[message(ret = u32)]
+struct Write {
+    fail: bool,
+}
+
+#[message(ret = u32)]
+struct Probe;
+
+#[message]
+struct Replicate;
+
+fn replicas() -> Blueprint {
+    ActorGroup::new()
+        .router(MapRouter::new(|envelope| {
+            msg!(match envelope {
+                Write | Probe | Replicate => Outcome::Multicast(vec![0, 1, 2]),
+                _ => Outcome::Default,
+            })
+        }))
+        .exec(|mut ctx: Context<(), u32>| async move {
+            let key = *ctx.key();
+            let mut pending = Vec::new();
+            let mut pending_probes = Vec::new();
+
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    (Write { fail }, token) => match key {
+                        0 => ctx.respond(token, key),
+                        1 if !fail => {
+                            tokio::time::sleep(Duration::from_millis(10)).await;
+                            ctx.respond(token, key);
+                        }
+                        // The slowest replica never responds.
+                        2 if !fail => pending.push(token),
+                        // Dropping the token leads to `RequestError::Ignored`.
+                        _ => drop(token),
+                    },
+                    (Probe, token) => match key {
+                        0 => drop(token),
+                        _ => pending_probes.push(token),
+                    },
+                    Replicate => {}
+                });
+            }
+        })
+}
+
+#[tokio::test]
+async fn first_and_quorum() {
+    let (tx, rx) = futures_intrusive::channel::shared::oneshot_channel();
+    let tx = Arc::new(tx);
+
+    let requester = ActorGroup::new().exec(move |ctx| {
+        let tx = tx.clone();
+
+        async move {
+            // Start all replicas.
+            ctx.send(Replicate).await.unwrap();
+
+            let results = vec![
+                format!(
+                    "{:?}",
+                    ctx.request(Write { fail: false }).first(2).resolve().await
+                ),
+                format!(
+                    "{:?}",
+                    ctx.request(Write { fail: false }).quorum(2).resolve().await
+                ),
+                format!(
+                    "{:?}",
+                    ctx.request(Write { fail: true }).first(2).resolve().await
+                ),
+                format!(
+                    "{:?}",
+                    ctx.request(Write { fail: true }).quorum(2).resolve().await
+                ),
+                format!(
+                    "{:?}",
+                    ctx.request(Write { fail: false })
+                        .quorum(3)
+                        .timeout(Duration::from_millis(50))
+                        .resolve()
+                        .await
+                ),
+                // Zero responses are rejected.
+                format!(
+                    "{:?}",
+                    panic::catch_unwind(AssertUnwindSafe(|| {
+                        let _ = ctx.request(Write { fail: false }).first(0);
+                    }))
+                    .is_err()
+                ),
+                format!(
+                    "{:?}",
+                    panic::catch_unwind(AssertUnwindSafe(|| {
+                        let _ = ctx.request(Write { fail: false }).quorum(0);
+                    }))
+                    .is_err()
+                ),
+                // The received error is kept instead of `TimedOut`.
+                format!(
+                    "{:?}",
+                    ctx.request(Probe)
+                        .timeout(Duration::from_millis(50))
+                        .resolve()
+                        .await
+                ),
+            ];
+
+            tx.send(results).unwrap();
+        }
+    });
+
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    let requesters = topology.local("requesters");
+    let replica_group = topology.local("replicas");
+
+    requesters.route_all_to(&replica_group);
+
+    configurers.mount(elfo_configurer::fixture(&topology, AnyConfig::default()));
+    requesters.mount(requester);
+    replica_group.mount(replicas());
+
+    do_start(topology, false, |_, _| futures::future::ready(()))
+        .await
+        .expect("cannot start");
+
+    let results = rx.receive().await.unwrap();
+    assert_eq!(
+        results,
+        [
+            "[0, 1]",
+            "Ok([0, 1])",
+            "[0]",
+            "Err(Ignored)",
+            "Err(TimedOut)",
+            "true",
+            "true",
+            "Err(Ignored)",
+        ]
+    );
+}
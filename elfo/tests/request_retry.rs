@@ -0,0 +1,145 @@
+#![cfg(feature = "test-util")]
+
+use std::{
+    num::NonZeroU64,
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
+
+use elfo::{config::AnyConfig, prelude::*, test::extract_request, RetryPolicy};
+
+#[message(ret = u32)]
+struct Write;
+
+#[message]
+enum Start {
+    Retry { max_retries: u64 },
+    Hedge,
+    HedgeTo,
+}
+
+#[message]
+#[derive(PartialEq)]
+struct Response(Result<u32, String>);
+
+#[message]
+#[derive(PartialEq)]
+struct Rejected(bool);
+
+fn blueprint() -> Blueprint {
+    ActorGroup::new().exec(|mut ctx| async move {
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Start::Retry { max_retries } => {
+                    let policy = RetryPolicy::new(Duration::from_secs(1), Duration::from_secs(5))
+                        .max_retries(NonZeroU64::new(max_retries));
+                    let response = ctx
+                        .request(Write)
+                        .timeout(Duration::from_secs(1))
+                        .retry(policy)
+                        .resolve()
+                        .await;
+                    let response = response.map_err(|err| err.to_string());
+                    ctx.send(Response(response)).await.unwrap();
+                }
+                Start::Hedge => {
+                    let after = Duration::from_secs(1);
+                    let response = ctx.request(Write).hedge(after).resolve().await;
+                    let response = response.map_err(|err| err.to_string());
+                    ctx.send(Response(response)).await.unwrap();
+                }
+                Start::HedgeTo => {
+                    let after = Duration::from_secs(1);
+                    let rejected = panic::catch_unwind(AssertUnwindSafe(|| {
+                        let _ = ctx.request_to(ctx.addr(), Write).hedge(after);
+                    }));
+                    ctx.send(Rejected(rejected.is_err())).await.unwrap();
+                }
+            });
+        }
+    })
+}
+
+#[tokio::test(start_paused = true)]
+async fn retry() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    // Succeeded after retries, all attempts have the same trace id.
+    proxy.send(Start::Retry { max_retries: 3 }).await;
+    let envelope = proxy.recv().await;
+    let trace_id = envelope.trace_id();
+    let (_, _timed_out1) = extract_request::<Write>(envelope);
+
+    let envelope = proxy.recv().await;
+    assert_eq!(envelope.trace_id(), trace_id);
+    let (_, _timed_out2) = extract_request::<Write>(envelope);
+
+    let envelope = proxy.recv().await;
+    assert_eq!(envelope.trace_id(), trace_id);
+    let (_, token) = extract_request::<Write>(envelope);
+    proxy.respond(token, 42);
+    assert_msg_eq!(proxy.recv().await, Response(Ok(42)));
+
+    // Retries are exhausted.
+    proxy.send(Start::Retry { max_retries: 1 }).await;
+    let (_, _timed_out1) = extract_request::<Write>(proxy.recv().await);
+    let (_, _timed_out2) = extract_request::<Write>(proxy.recv().await);
+    let expected = Err("request timed out".into());
+    assert_msg_eq!(proxy.recv().await, Response(expected));
+
+    // Ignored requests aren't retried.
+    proxy.send(Start::Retry { max_retries: 3 }).await;
+    drop(extract_request::<Write>(proxy.recv().await));
+    assert_msg_eq!(proxy.recv().await, Response(Err("request ignored".into())));
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedge() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    // The copy is responded first.
+    proxy.send(Start::Hedge).await;
+    let envelope = proxy.recv().await;
+    let trace_id = envelope.trace_id();
+    let (_, slow_token) = extract_request::<Write>(envelope);
+
+    let envelope = proxy.recv().await;
+    assert_eq!(envelope.trace_id(), trace_id);
+    let (_, token) = extract_request::<Write>(envelope);
+    proxy.respond(token, 2);
+    assert_msg_eq!(proxy.recv().await, Response(Ok(2)));
+
+    // The late response is dropped.
+    proxy.respond(slow_token, 1);
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+
+    // The original request is responded first, the copy is ignored.
+    proxy.send(Start::Hedge).await;
+    let (_, token) = extract_request::<Write>(proxy.recv().await);
+    let (_, copy_token) = extract_request::<Write>(proxy.recv().await);
+    proxy.respond(token, 1);
+    assert_msg_eq!(proxy.recv().await, Response(Ok(1)));
+    drop(copy_token);
+
+    // Responded in time, no copies are sent.
+    proxy.send(Start::Hedge).await;
+    let (_, token) = extract_request::<Write>(proxy.recv().await);
+    proxy.respond(token, 3);
+    assert_msg_eq!(proxy.recv().await, Response(Ok(3)));
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn hedge_to() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+    // Printing the caught panic can be slow on loaded machines.
+    proxy.set_recv_timeout(Duration::from_secs(5));
+
+    // The copy would reach the same actor.
+    proxy.send(Start::HedgeTo).await;
+    assert_msg_eq!(proxy.recv().await, Rejected(true));
+}
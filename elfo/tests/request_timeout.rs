@@ -0,0 +1,80 @@
+#![cfg(feature = "test-util")]
+
+use std::time::Duration;
+
+use elfo::{config::AnyConfig, errors::RequestError, prelude::*, test::extract_request};
+
+#[message(ret = u32)]
+struct Slow;
+
+#[message]
+struct Start {
+    all: bool,
+}
+
+#[message]
+#[derive(PartialEq)]
+struct Responses(Vec<Result<u32, String>>);
+
+fn blueprint() -> Blueprint {
+    ActorGroup::new().exec(|mut ctx| async move {
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Start { all } => {
+                    let timeout = Duration::from_secs(1);
+                    let responses = if all {
+                        ctx.request(Slow).all().timeout(timeout).resolve().await
+                    } else {
+                        vec![ctx.request(Slow).timeout(timeout).resolve().await]
+                    };
+                    let responses = responses
+                        .into_iter()
+                        .map(|r| r.map_err(|err| err.to_string()))
+                        .collect();
+                    ctx.send(Responses(responses)).await.unwrap();
+                }
+            });
+        }
+    })
+}
+
+fn timed_out() -> Result<u32, String> {
+    Err(RequestError::TimedOut.to_string())
+}
+
+#[tokio::test(start_paused = true)]
+async fn any() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    // Responded in time.
+    proxy.send(Start { all: false }).await;
+    let (_, token) = extract_request::<Slow>(proxy.recv().await);
+    proxy.respond(token, 42);
+    assert_msg_eq!(proxy.recv().await, Responses(vec![Ok(42)]));
+
+    // Timed out, the late response is dropped.
+    proxy.send(Start { all: false }).await;
+    let (_, token) = extract_request::<Slow>(proxy.recv().await);
+    assert_msg_eq!(proxy.recv().await, Responses(vec![timed_out()]));
+    proxy.respond(token, 42);
+
+    proxy.send(Start { all: false }).await;
+    let (_, token) = extract_request::<Slow>(proxy.recv().await);
+    proxy.respond(token, 43);
+    assert_msg_eq!(proxy.recv().await, Responses(vec![Ok(43)]));
+}
+
+#[tokio::test(start_paused = true)]
+async fn all() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    proxy.send(Start { all: true }).await;
+    let (_, token) = extract_request::<Slow>(proxy.recv().await);
+    assert_msg_eq!(proxy.recv().await, Responses(vec![timed_out()]));
+    proxy.respond(token, 42);
+
+    proxy.send(Start { all: true }).await;
+    let (_, token) = extract_request::<Slow>(proxy.recv().await);
+    proxy.respond(token, 43);
+    assert_msg_eq!(proxy.recv().await, Responses(vec![Ok(43)]));
+}
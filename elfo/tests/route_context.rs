@@ -0,0 +1,119 @@
+#![cfg(feature = "test-util")]
+
+use std::sync::{Arc, Mutex};
+
+use elfo::{
+    config::AnyConfig,
+    prelude::*,
+    routers::{Outcome, RouteContext, Router},
+    ActorStatusKind, Envelope, RestartParams, RestartPolicy,
+};
+
+#[message(ret = u32)]
+struct Work;
+
+#[message]
+struct Fail;
+
+#[message(ret = ())]
+struct Probe;
+
+#[message]
+struct Restarted;
+
+#[derive(Debug, PartialEq)]
+struct Seen {
+    keys: Vec<u32>,
+    status: Option<ActorStatusKind>,
+    spawn_count: Option<u32>,
+}
+
+/// Routes to any live actor, otherwise spawns the actor with the key `7`.
+struct AnyLive(Arc<Mutex<Vec<Seen>>>);
+
+impl Router<()> for AnyLive {
+    type Key = u32;
+
+    fn route(&self, _envelope: &Envelope) -> Outcome<Self::Key> {
+        unreachable!("the supervisor provides the context")
+    }
+
+    fn route_with_context(
+        &self,
+        envelope: &Envelope,
+        ctx: &RouteContext<'_, Self::Key>,
+    ) -> Outcome<Self::Key> {
+        let key = ctx.keys().min().unwrap_or(7);
+
+        msg!(match envelope {
+            Work | Fail => Outcome::Unicast(key),
+            Probe => {
+                let mut keys = ctx.keys().collect::<Vec<_>>();
+                keys.sort_unstable();
+
+                self.0.lock().unwrap().push(Seen {
+                    keys,
+                    status: ctx.status(&key),
+                    spawn_count: ctx.spawn_count(&key),
+                });
+
+                Outcome::GentleUnicast(key)
+            }
+            _ => Outcome::Default,
+        })
+    }
+}
+
+#[tokio::test]
+async fn live_keys() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let blueprint = ActorGroup::new()
+        .router(AnyLive(seen.clone()))
+        .restart_policy(RestartPolicy::on_failure(RestartParams::new(
+            Default::default(),
+            Default::default(),
+        )))
+        .exec(|mut ctx| async move {
+            if ctx.start_info().cause.is_restarted() {
+                ctx.send(Restarted).await.unwrap();
+            }
+
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    (Work, token) => ctx.respond(token, *ctx.key()),
+                    Fail => anyhow::bail!("failed"),
+                    (Probe, token) => ctx.respond(token, ()),
+                });
+            }
+
+            Ok(())
+        });
+
+    let mut proxy = elfo::test::proxy(blueprint, AnyConfig::default()).await;
+
+    assert_eq!(proxy.request(Work).await, 7);
+    proxy.request(Probe).await;
+
+    // The restarted actor is used.
+    proxy.send(Fail).await;
+    assert_msg!(proxy.recv().await, Restarted);
+    assert_eq!(proxy.request(Work).await, 7);
+    proxy.request(Probe).await;
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        [
+            Seen {
+                keys: vec![7],
+                status: Some(ActorStatusKind::Normal),
+                spawn_count: Some(1),
+            },
+            Seen {
+                keys: vec![7],
+                status: Some(ActorStatusKind::Normal),
+                spawn_count: Some(2),
+            },
+        ]
+    );
+}
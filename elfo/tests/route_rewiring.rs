@@ -0,0 +1,113 @@
+#![cfg(feature = "test-util")]
+
+use tokio::sync::mpsc;
+
+use elfo::{_priv::do_start, config::AnyConfig, prelude::*, topology::ConnectionTo, Topology};
+
+#[message]
+struct Tick;
+
+#[message]
+struct Order;
+
+fn strategy(tx: mpsc::UnboundedSender<&'static str>) -> Blueprint {
+    ActorGroup::new().exec(move |mut ctx| {
+        let tx = tx.clone();
+        async move {
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    Tick =>
+                        if ctx.send(Order).await.is_err() {
+                            tx.send("unrouted").unwrap();
+                        },
+                    _ => {}
+                });
+            }
+        }
+    })
+}
+
+fn downstream(name: &'static str, tx: mpsc::UnboundedSender<&'static str>) -> Blueprint {
+    ActorGroup::new().exec(move |mut ctx| {
+        let tx = tx.clone();
+        async move {
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    Order => tx.send(name).unwrap(),
+                    _ => {}
+                });
+            }
+        }
+    })
+}
+
+#[tokio::test]
+async fn rewiring() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    let strategy_group = topology.local("strategy");
+    let strategy_addr = strategy_group.addr();
+    let exchange = topology.local("exchange");
+    let simulator = topology.local("simulator");
+
+    strategy_group.route_to(&exchange, |e| {
+        msg!(match e {
+            Order => true,
+            _ => false,
+        })
+    });
+
+    configurers.mount(elfo_configurer::fixture(&topology, AnyConfig::default()));
+    strategy_group.mount(strategy(tx.clone()));
+    exchange.mount(downstream("exchange", tx.clone()));
+    simulator.mount(downstream("simulator", tx.clone()));
+
+    let destinations = |topology: &Topology| {
+        let names = topology
+            .locals()
+            .map(|group| (group.addr, group.name))
+            .collect::<Vec<_>>();
+
+        topology
+            .connections()
+            .filter(|conn| conn.from == strategy_addr)
+            .filter_map(|conn| match conn.to {
+                ConnectionTo::Local(to) => names.iter().find(|(addr, _)| *addr == to),
+                _ => None,
+            })
+            .map(|(_, name)| name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    do_start(topology, false, |ctx, topology| async move {
+        ctx.send_to(strategy_addr, Tick).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), "exchange");
+
+        // The filter is kept.
+        assert!(topology.replace_route("strategy", "exchange", "simulator"));
+        assert!(!topology.replace_route("strategy", "exchange", "simulator"));
+        assert_eq!(destinations(&topology), ["simulator"]);
+        ctx.send_to(strategy_addr, Tick).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), "simulator");
+
+        assert!(topology.remove_route("strategy", "simulator"));
+        assert!(!topology.remove_route("strategy", "simulator"));
+        assert!(destinations(&topology).is_empty());
+        ctx.send_to(strategy_addr, Tick).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), "unrouted");
+
+        topology.add_route("strategy", "exchange", |e| {
+            msg!(match e {
+                Order => true,
+                _ => false,
+            })
+        });
+        assert_eq!(destinations(&topology), ["exchange"]);
+        ctx.send_to(strategy_addr, Tick).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), "exchange");
+    })
+    .await
+    .expect("cannot start");
+}
@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use elfo::{config::AnyConfig, prelude::*, scope, time::Debounce, UnattachedSource};
+use tokio::time::Instant;
+
+#[message]
+struct Push(u32);
+
+#[message]
+#[derive(PartialEq, Eq)]
+struct Emitted(u32);
+
+fn sample(debounce: fn(Duration) -> UnattachedSource<Debounce<Emitted>>) -> Blueprint {
+    ActorGroup::new().exec(move |mut ctx| async move {
+        let debounce = ctx.attach(debounce(Duration::from_millis(100)));
+        let mut trace_ids = Vec::new();
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Push(n) => {
+                    trace_ids.push((n, scope::trace_id()));
+                    debounce.push(Emitted(n));
+                }
+                msg @ Emitted(n) => {
+                    let expected = trace_ids.iter().find(|(i, _)| *i == n).unwrap().1;
+                    assert_eq!(scope::trace_id(), expected);
+                    trace_ids.clear();
+                    ctx.send(msg).await.unwrap();
+                }
+            });
+        }
+    })
+}
+
+async fn burst(proxy: &mut elfo::test::Proxy, from: u32, to: u32) {
+    for n in from..=to {
+        proxy.send(Push(n)).await;
+        proxy.sync().await;
+        tokio::time::advance(Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn last() {
+    let mut proxy = elfo::test::proxy(sample(Debounce::last), AnyConfig::default()).await;
+    assert!(proxy.try_recv().await.is_none());
+
+    // Messages pushed more often than the period are collapsed.
+    burst(&mut proxy, 1, 5).await;
+    assert!(proxy.try_recv().await.is_none());
+
+    let start = Instant::now();
+    assert_msg_eq!(proxy.recv().await, Emitted(5));
+    assert_eq!(start.elapsed(), Duration::from_millis(50));
+    assert!(proxy.try_recv().await.is_none());
+
+    // The next burst is emitted separately.
+    burst(&mut proxy, 6, 7).await;
+    assert_msg_eq!(proxy.recv().await, Emitted(7));
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+    assert!(proxy.try_recv().await.is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn first() {
+    let mut proxy = elfo::test::proxy(sample(Debounce::first), AnyConfig::default()).await;
+
+    burst(&mut proxy, 1, 5).await;
+    assert!(proxy.try_recv().await.is_none());
+    assert_msg_eq!(proxy.recv().await, Emitted(1));
+    assert!(proxy.try_recv().await.is_none());
+
+    burst(&mut proxy, 6, 7).await;
+    assert_msg_eq!(proxy.recv().await, Emitted(6));
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+    assert!(proxy.try_recv().await.is_none());
+}
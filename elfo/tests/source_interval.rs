@@ -1,6 +1,12 @@
 use std::time::Duration;
 
-use elfo::{config::AnyConfig, prelude::*, scope, test::Proxy, time::Interval};
+use elfo::{
+    config::AnyConfig,
+    prelude::*,
+    scope,
+    test::Proxy,
+    time::{Interval, MissedTickPolicy},
+};
 use tokio::time::{sleep, Instant};
 
 fn ms(millis: u64) -> Duration {
@@ -95,6 +101,12 @@ struct Start(Duration);
 #[message]
 struct StartAfter(Duration, Duration);
 
+#[message]
+struct StartWithJitter(Duration, Duration);
+
+#[message]
+struct SetTickJitter(Duration);
+
 #[message]
 struct Stop;
 
@@ -111,9 +123,17 @@ struct Tick(u32);
 #[message]
 struct Terminate;
 
+#[message]
+struct Block(Duration);
+
 fn sample() -> Blueprint {
+    sample_with_policy(MissedTickPolicy::Burst)
+}
+
+fn sample_with_policy(policy: MissedTickPolicy) -> Blueprint {
     ActorGroup::new().exec(move |mut ctx| async move {
         let mut interval = Some(ctx.attach(Interval::new(Tick(0))));
+        interval.as_ref().unwrap().set_missed_tick_policy(policy);
 
         while let Some(envelope) = ctx.recv().await {
             msg!(match envelope {
@@ -123,6 +143,12 @@ fn sample() -> Blueprint {
                 StartAfter(delay, period) => {
                     interval.as_ref().unwrap().start_after(delay, period);
                 }
+                StartWithJitter(period, jitter) => {
+                    interval.as_ref().unwrap().start_with_jitter(period, jitter);
+                }
+                SetTickJitter(jitter) => {
+                    interval.as_ref().unwrap().set_tick_jitter(jitter);
+                }
                 Stop => {
                     interval.as_ref().unwrap().stop();
                 }
@@ -135,6 +161,9 @@ fn sample() -> Blueprint {
                 Terminate => {
                     interval.take().unwrap().terminate();
                 }
+                Block(duration) => {
+                    sleep(duration).await;
+                }
                 msg @ Tick => {
                     ctx.send(msg).await.unwrap();
                 }
@@ -258,6 +287,106 @@ async fn burst() {
     checker.tick(ms(10), 0).await;
 }
 
+async fn missed_ticks(policy: MissedTickPolicy, expected: &[u64]) {
+    let proxy = elfo::test::proxy(sample_with_policy(policy), AnyConfig::default()).await;
+    let mut checker = Checker::new(proxy);
+
+    checker.send(Start(ms(10))).await;
+    checker.send(Block(ms(35))).await;
+
+    for elapsed in expected {
+        checker.tick(ms(*elapsed), 0).await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn missed_tick_policy_burst() {
+    missed_ticks(MissedTickPolicy::Burst, &[35, 0, 0, 5, 10]).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn missed_tick_policy_delay() {
+    missed_ticks(MissedTickPolicy::Delay, &[35, 10, 10]).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn missed_tick_policy_skip() {
+    missed_ticks(MissedTickPolicy::Skip, &[35, 5, 10]).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn missed_tick_policy_with_set_period() {
+    let proxy = elfo::test::proxy(
+        sample_with_policy(MissedTickPolicy::Skip),
+        AnyConfig::default(),
+    )
+    .await;
+    let mut checker = Checker::new(proxy);
+
+    checker.send(Start(ms(10))).await;
+    checker.send(SetPeriod(ms(20))).await;
+    checker.send(Block(ms(35))).await;
+
+    // The tick at 20ms is missed, the next one is aligned to the new period.
+    checker.tick(ms(35), 0).await;
+    checker.tick(ms(5), 0).await;
+    checker.tick(ms(20), 0).await;
+}
+
+async fn start_with_jitter_once(seed: u64) -> Duration {
+    elfo::time::seed_jitter(seed);
+
+    let proxy = elfo::test::proxy(sample(), AnyConfig::default()).await;
+    let mut checker = Checker::new(proxy);
+
+    checker.send(StartWithJitter(ms(1000), ms(1000))).await;
+    assert_msg_eq!(checker.proxy.recv().await, Tick(0));
+    let shift = checker.prev_time.elapsed() - ms(1000);
+    assert!(shift <= ms(1000), "{shift:?}");
+
+    // The shift is kept for next ticks.
+    checker.prev_time = Instant::now();
+    checker.tick(ms(1000), 0).await;
+    checker.tick(ms(1000), 0).await;
+    shift
+}
+
+#[tokio::test(start_paused = true)]
+async fn start_with_jitter() {
+    let shifts = [
+        start_with_jitter_once(42).await,
+        start_with_jitter_once(42).await,
+        start_with_jitter_once(43).await,
+    ];
+
+    // Seeded shifts are deterministic.
+    assert_eq!(shifts[0], shifts[1]);
+    assert_ne!(shifts[0], shifts[2]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn tick_jitter() {
+    elfo::time::seed_jitter(42);
+
+    let mut proxy = elfo::test::proxy(sample(), AnyConfig::default()).await;
+    proxy.send(SetTickJitter(ms(5))).await;
+    proxy.send(Start(ms(10))).await;
+    let start = Instant::now();
+
+    let mut shifts = Vec::new();
+    for no in 1..=10 {
+        assert_msg_eq!(proxy.recv().await, Tick(0));
+
+        // Ticks are shifted, but the schedule isn't.
+        let shift = start.elapsed() - ms(10) * no;
+        assert!(shift <= ms(5), "{shift:?}");
+        shifts.push(shift);
+    }
+
+    shifts.dedup();
+    assert!(shifts.len() > 1);
+}
+
 #[tokio::test(start_paused = true)]
 async fn terminate() {
     let mut proxy = elfo::test::proxy(sample(), AnyConfig::default()).await;
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use elfo::{
+    config::AnyConfig,
+    prelude::*,
+    time::{Calendar, Schedule},
+};
+
+#[message]
+struct Tick;
+
+#[message]
+struct Pause;
+
+#[tokio::test]
+async fn cron() {
+    let group = ActorGroup::new().exec(|mut ctx| async move {
+        let every_second = Calendar::cron("* * * * * *").unwrap();
+        let schedule = ctx.attach(Schedule::new(every_second, Tick));
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Tick => ctx.send(Tick).await.unwrap(),
+                Pause => {
+                    // Far from now.
+                    let calendar = Calendar::cron("0 0 0 1 1 *").unwrap();
+                    schedule.set_calendar(calendar);
+                }
+            });
+        }
+    });
+
+    let mut proxy = elfo::test::proxy(group, AnyConfig::default()).await;
+    proxy.set_recv_timeout(Duration::from_secs(2));
+
+    // Times and clock jumps are tested with the mocked clock in `elfo-core`.
+    assert_msg!(proxy.recv().await, Tick);
+
+    proxy.send(Pause).await;
+    proxy.sync().await;
+    // A tick could be emitted before `Pause` is handled.
+    while proxy.try_recv().await.is_some() {}
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert!(proxy.try_recv().await.is_none());
+}
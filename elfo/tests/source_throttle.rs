@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use elfo::{config::AnyConfig, prelude::*, scope, time::Throttle, UnattachedSource};
+use tokio::time::Instant;
+
+#[message]
+struct Push(u32);
+
+#[message]
+#[derive(PartialEq, Eq)]
+struct Emitted(u32);
+
+fn sample(throttle: fn(Duration) -> UnattachedSource<Throttle<Emitted>>) -> Blueprint {
+    ActorGroup::new().exec(move |mut ctx| async move {
+        let throttle = ctx.attach(throttle(Duration::from_millis(100)));
+        let mut trace_ids = Vec::new();
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Push(n) => {
+                    trace_ids.push((n, scope::trace_id()));
+                    throttle.push(Emitted(n));
+                }
+                msg @ Emitted(n) => {
+                    let expected = trace_ids.iter().find(|(i, _)| *i == n).unwrap().1;
+                    assert_eq!(scope::trace_id(), expected);
+                    ctx.send(msg).await.unwrap();
+                }
+            });
+        }
+    })
+}
+
+#[tokio::test(start_paused = true)]
+async fn last() {
+    let mut proxy = elfo::test::proxy(sample(Throttle::last), AnyConfig::default()).await;
+    assert!(proxy.try_recv().await.is_none());
+
+    // The first message is emitted immediately.
+    let start = Instant::now();
+    proxy.send(Push(1)).await;
+    assert_msg_eq!(proxy.recv().await, Emitted(1));
+
+    // Others are collapsed until the end of the window.
+    proxy.send(Push(2)).await;
+    proxy.send(Push(3)).await;
+    assert!(proxy.try_recv().await.is_none());
+    assert_msg_eq!(proxy.recv().await, Emitted(3));
+    assert_eq!(start.elapsed(), Duration::from_millis(100));
+
+    // The emitted message opens the next window.
+    proxy.send(Push(4)).await;
+    assert!(proxy.try_recv().await.is_none());
+    assert_msg_eq!(proxy.recv().await, Emitted(4));
+    assert_eq!(start.elapsed(), Duration::from_millis(200));
+
+    // The window is closed if nothing has been pushed inside it.
+    tokio::time::advance(Duration::from_millis(150)).await;
+    assert!(proxy.try_recv().await.is_none());
+    proxy.send(Push(5)).await;
+    assert_msg_eq!(proxy.recv().await, Emitted(5));
+    assert_eq!(start.elapsed(), Duration::from_millis(350));
+}
+
+#[tokio::test(start_paused = true)]
+async fn first() {
+    let mut proxy = elfo::test::proxy(sample(Throttle::first), AnyConfig::default()).await;
+
+    proxy.send(Push(1)).await;
+    assert_msg_eq!(proxy.recv().await, Emitted(1));
+
+    proxy.send(Push(2)).await;
+    proxy.send(Push(3)).await;
+    assert!(proxy.try_recv().await.is_none());
+    assert_msg_eq!(proxy.recv().await, Emitted(2));
+    assert!(proxy.try_recv().await.is_none());
+
+    tokio::time::advance(Duration::from_millis(150)).await;
+    assert!(proxy.try_recv().await.is_none());
+}
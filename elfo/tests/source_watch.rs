@@ -0,0 +1,208 @@
+#![cfg(feature = "test-util")]
+
+use std::{fs, path::Path, time::Duration};
+
+use serde::Deserialize;
+use tokio::sync::mpsc;
+
+use elfo::{
+    _priv::do_start, config::AnyConfig, messages::ConfigUpdated, prelude::*, test::Proxy,
+    watch::Watch, Topology,
+};
+
+#[message]
+struct Start {
+    path: String,
+    polling: bool,
+}
+
+#[message]
+#[derive(PartialEq, Eq)]
+struct Changed;
+
+async fn sample(path: &Path, polling: bool) -> Proxy {
+    let group = ActorGroup::new().exec(|mut ctx| async move {
+        let mut watch = None;
+
+        while let Some(envelope) = ctx.recv().await {
+            msg!(match envelope {
+                Start { path, polling } => {
+                    watch = Some(ctx.attach(if polling {
+                        Watch::polling(path, Duration::from_millis(10), Changed)
+                    } else {
+                        Watch::new(path, Changed)
+                    }));
+                }
+                Changed => {
+                    assert!(!watch.as_ref().unwrap().is_terminated());
+                    ctx.send(Changed).await.unwrap();
+                }
+            });
+        }
+    });
+
+    let mut proxy = elfo::test::proxy(group, AnyConfig::default()).await;
+    proxy.set_recv_timeout(Duration::from_secs(5));
+
+    let path = path.to_str().unwrap().into();
+    proxy.send(Start { path, polling }).await;
+    proxy.sync().await;
+    proxy
+}
+
+// Events are delivered asynchronously, so wait a bit to check that there are
+// no more messages.
+async fn assert_no_changes(proxy: &mut Proxy) {
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(proxy.try_recv().await.is_none());
+}
+
+#[tokio::test]
+async fn file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.txt");
+    let mut proxy = sample(&path, false).await;
+
+    // Creating.
+    fs::write(&path, "1").unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    // Other files are ignored.
+    fs::write(dir.path().join("other.txt"), "1").unwrap();
+    assert_no_changes(&mut proxy).await;
+
+    // Modifying.
+    fs::write(&path, "2").unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    // Replacing by renaming.
+    fs::rename(dir.path().join("other.txt"), &path).unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    // Removing.
+    fs::remove_file(&path).unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn swapped_symlink() {
+    use std::os::unix::fs::symlink;
+
+    // The layout of mounted Kubernetes ConfigMaps.
+    let dir = tempfile::tempdir().unwrap();
+    for version in ["..v1", "..v2"] {
+        fs::create_dir(dir.path().join(version)).unwrap();
+        fs::write(dir.path().join(version).join("data.txt"), version).unwrap();
+    }
+    symlink("..v1", dir.path().join("..data")).unwrap();
+    symlink("..v2", dir.path().join("..data_tmp")).unwrap();
+    symlink("..data/data.txt", dir.path().join("data.txt")).unwrap();
+
+    let mut proxy = sample(&dir.path().join("data.txt"), false).await;
+
+    // The file itself isn't touched, only the intermediate symlink.
+    fs::rename(dir.path().join("..data_tmp"), dir.path().join("..data")).unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    fs::remove_dir_all(dir.path().join("..v1")).unwrap();
+    assert_no_changes(&mut proxy).await;
+}
+
+#[tokio::test]
+async fn directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data");
+    fs::create_dir(&path).unwrap();
+    let mut proxy = sample(&path, false).await;
+
+    fs::write(path.join("a.txt"), "1").unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    fs::remove_file(path.join("a.txt")).unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    // The directory is removed, then polled until it's created again.
+    fs::remove_dir(&path).unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    fs::create_dir(&path).unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+}
+
+#[tokio::test]
+async fn polling() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("data.txt");
+    let mut proxy = sample(&path, true).await;
+    // The first fingerprint is read in the background.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    fs::write(&path, "1").unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    // Sizes are changed, because modification times can be coarse.
+    fs::write(&path, "22").unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+
+    fs::remove_file(&path).unwrap();
+    assert_msg_eq!(proxy.recv().await, Changed);
+    assert_no_changes(&mut proxy).await;
+}
+
+#[tokio::test]
+async fn configurer_reloads_watched_file() {
+    #[derive(Debug, Clone, Deserialize)]
+    struct Config {
+        greeting: String,
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    fs::write(&path, "[tenant]\ngreeting = \"hi\"\n").unwrap();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    configurers.mount(elfo_configurer::from_path_watched(&topology, &path));
+    topology
+        .local("tenant")
+        .mount(ActorGroup::new().config::<Config>().exec(move |mut ctx| {
+            let tx = tx.clone();
+            async move {
+                tx.send(ctx.config().greeting.clone()).unwrap();
+
+                while let Some(envelope) = ctx.recv().await {
+                    msg!(match envelope {
+                        ConfigUpdated => tx.send(ctx.config().greeting.clone()).unwrap(),
+                        _ => {}
+                    });
+                }
+            }
+        }));
+
+    do_start(topology, false, |_ctx, _topology| async move {
+        assert_eq!(rx.recv().await.unwrap(), "hi");
+
+        // Replace the file like editors do.
+        let tmp = dir.path().join("config.toml.tmp");
+        fs::write(&tmp, "[tenant]\ngreeting = \"hello\"\n").unwrap();
+        fs::rename(&tmp, &path).unwrap();
+
+        let greeting = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await;
+        assert_eq!(greeting.unwrap().unwrap(), "hello");
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "test-util")]
+
+use toml::toml;
+
+use elfo::{config::AnyConfig, prelude::*};
+
+#[message]
+struct Connected;
+
+#[message]
+#[derive(PartialEq)]
+struct Query(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Handled(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Rejected(u32);
+
+fn blueprint() -> Blueprint {
+    ActorGroup::new().exec(|mut ctx| async move {
+        let mut connected = false;
+
+        while let Some(envelope) = ctx.recv().await {
+            if !connected && envelope.is::<Query>() {
+                if let Err(err) = ctx.stash(envelope) {
+                    let no = err.0.message().downcast_ref::<Query>().unwrap().0;
+                    ctx.send(Rejected(no)).await.unwrap();
+                }
+                continue;
+            }
+
+            msg!(match envelope {
+                Connected => {
+                    connected = true;
+                    ctx.unstash_all();
+                }
+                Query(no) => ctx.send(Handled(no)).await.unwrap(),
+            });
+        }
+    })
+}
+
+#[tokio::test]
+async fn unstashed_envelopes_are_received_first() {
+    let mut proxy = elfo::test::proxy(blueprint(), AnyConfig::default()).await;
+
+    proxy.send(Query(1)).await;
+    proxy.send(Query(2)).await;
+    proxy.sync().await;
+    assert!(proxy.try_recv().await.is_none());
+
+    proxy.send(Connected).await;
+    proxy.send(Query(3)).await;
+
+    for no in 1..=3 {
+        assert_msg_eq!(proxy.recv().await, Handled(no));
+    }
+}
+
+#[tokio::test]
+async fn stash_capacity() {
+    let config = toml! {
+        [system.mailbox]
+        stash_capacity = 2
+    };
+    let mut proxy = elfo::test::proxy(blueprint(), config).await;
+
+    for no in 1..=3 {
+        proxy.send(Query(no)).await;
+    }
+    assert_msg_eq!(proxy.recv().await, Rejected(3));
+
+    proxy.send(Connected).await;
+    assert_msg_eq!(proxy.recv().await, Handled(1));
+    assert_msg_eq!(proxy.recv().await, Handled(2));
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "test-util")]
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use toml::toml;
+
+use elfo::{
+    config::AnyConfig,
+    prelude::*,
+    routers::{MapRouter, Outcome},
+    RestartParams, RestartPolicy, SupervisionStrategy, Topology,
+};
+
+#[message]
+struct Touch(u32);
+
+#[message]
+struct Fail(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Started(u32);
+
+#[message]
+#[derive(PartialEq)]
+struct Stopped(u32);
+
+fn policy() -> RestartPolicy {
+    RestartPolicy::on_failure(RestartParams::new(Duration::ZERO, Duration::ZERO))
+}
+
+fn blueprint(policy: RestartPolicy) -> Blueprint {
+    ActorGroup::new()
+        .router(MapRouter::new(|envelope| {
+            msg!(match envelope {
+                Touch(key) | Fail(key) => Outcome::Unicast(*key),
+                _ => Outcome::Default,
+            })
+        }))
+        .restart_policy(policy)
+        .exec(|mut ctx| async move {
+            let key = *ctx.key();
+            ctx.send(Started(key)).await.unwrap();
+
+            while let Some(envelope) = ctx.recv().await {
+                msg!(match envelope {
+                    Touch => {}
+                    Fail => anyhow::bail!("failed"),
+                });
+            }
+
+            ctx.send(Stopped(key)).await.unwrap();
+            Ok(())
+        })
+}
+
+async fn start(proxy: &mut elfo::test::Proxy, keys: &[u32]) {
+    for &key in keys {
+        proxy.send(Touch(key)).await;
+        assert_msg_eq!(proxy.recv().await, Started(key));
+    }
+}
+
+/// Collects `Started` and `Stopped` messages until the group calms down.
+async fn collect(proxy: &mut elfo::test::Proxy) -> (Vec<u32>, Vec<u32>) {
+    let mut started = Vec::new();
+    let mut stopped = Vec::new();
+
+    proxy.sync().await;
+    while let Some(envelope) = proxy.try_recv().await {
+        msg!(match envelope {
+            Started(key) => started.push(key),
+            Stopped(key) => stopped.push(key),
+        });
+        proxy.sync().await;
+    }
+
+    started.sort_unstable();
+    stopped.sort_unstable();
+    (started, stopped)
+}
+
+#[tokio::test]
+async fn one_for_one() {
+    let mut proxy = elfo::test::proxy(blueprint(policy()), AnyConfig::default()).await;
+    start(&mut proxy, &[1, 2, 3]).await;
+
+    proxy.send(Fail(2)).await;
+    assert_msg_eq!(proxy.recv().await, Started(2));
+    assert_eq!(collect(&mut proxy).await, (vec![], vec![]));
+}
+
+#[tokio::test]
+async fn one_for_all() {
+    let policy = policy().strategy(SupervisionStrategy::OneForAll);
+    let mut proxy = elfo::test::proxy(blueprint(policy), AnyConfig::default()).await;
+    start(&mut proxy, &[1, 2, 3]).await;
+
+    proxy.send(Fail(2)).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(collect(&mut proxy).await, (vec![1, 2, 3], vec![1, 3]));
+}
+
+#[tokio::test]
+async fn rest_for_one() {
+    let config = toml! {
+        [system.restart_policy]
+        when = "OnFailure"
+        min_backoff = "0s"
+        max_backoff = "0s"
+        strategy = "RestForOne"
+    };
+    let mut proxy = elfo::test::proxy(blueprint(policy()), config).await;
+    start(&mut proxy, &[3, 1, 2]).await;
+
+    // Actors started after the failed one are restarted.
+    proxy.send(Fail(1)).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(collect(&mut proxy).await, (vec![1, 2], vec![2]));
+
+    // Restarted actors keep their order.
+    proxy.send(Fail(3)).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(collect(&mut proxy).await, (vec![1, 2, 3], vec![1, 2]));
+}
+
+#[tokio::test]
+async fn intensity() {
+    let policy = policy().intensity(2, Duration::from_secs(60));
+    let mut proxy = elfo::test::proxy(blueprint(policy), AnyConfig::default()).await;
+    start(&mut proxy, &[1, 2]).await;
+
+    for _ in 0..2 {
+        proxy.send(Fail(1)).await;
+        assert_msg_eq!(proxy.recv().await, Started(1));
+    }
+
+    // The third restart fails the group.
+    proxy.send(Fail(1)).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(collect(&mut proxy).await, (vec![], vec![2]));
+
+    // New actors aren't spawned.
+    assert!(proxy.try_send(Touch(3)).is_err());
+    assert_eq!(collect(&mut proxy).await, (vec![], vec![]));
+}
+
+#[tokio::test]
+async fn failed_groups_terminate_system_gracefully() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let failing = || {
+        ActorGroup::new()
+            .restart_policy(policy().intensity(0, Duration::from_secs(60)))
+            .exec(|_ctx| async { Err::<(), _>(anyhow::anyhow!("failed")) })
+    };
+
+    let topology = Topology::empty();
+    let configurers = topology.local("system.configurers").entrypoint();
+    configurers.mount(elfo_configurer::fixture(&topology, AnyConfig::default()));
+
+    // Several groups fail, but the termination is still graceful.
+    topology.local("failing1").mount(failing());
+    topology.local("failing2").mount(failing());
+    topology
+        .local("observer")
+        .mount(ActorGroup::new().exec(move |mut ctx| {
+            let tx = tx.clone();
+            async move {
+                while ctx.recv().await.is_some() {}
+                tx.send(()).unwrap();
+            }
+        }));
+
+    let started = elfo::init::try_start(topology);
+    let result = tokio::time::timeout(Duration::from_secs(10), started).await;
+    result.expect("the system isn't terminated").unwrap();
+    assert!(rx.try_recv().is_ok());
+}